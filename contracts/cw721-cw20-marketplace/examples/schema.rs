@@ -5,6 +5,7 @@ use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use cw721_cw20_marketplace::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 use cw721_cw20_marketplace::state::{Config, Token};
+use stake_dragons_utils::ownership::PendingOwnerResponse;
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -17,4 +18,5 @@ fn main() {
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(Config), &out_dir);
     export_schema(&schema_for!(Token), &out_dir);
+    export_schema(&schema_for!(PendingOwnerResponse), &out_dir);
 }
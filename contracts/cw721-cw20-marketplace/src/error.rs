@@ -1,4 +1,6 @@
 use cosmwasm_std::{OverflowError, StdError, Uint128};
+use stake_dragons_utils::migrate::MigrateError;
+use stake_dragons_utils::ownership::OwnershipError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -44,4 +46,10 @@ pub enum ContractError {
 
     #[error("Wrong input")]
     WrongInput {},
+
+    #[error("{0}")]
+    Ownership(#[from] OwnershipError),
+
+    #[error("{0}")]
+    Migrate(#[from] MigrateError),
 }
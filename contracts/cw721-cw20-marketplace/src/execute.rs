@@ -1,4 +1,4 @@
-use crate::msg::{ExecuteMsg, InstantiateMsg, ReceiveMsg};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, ReceiveMsg};
 use crate::state::{token_map, Config, Token, CONFIG};
 use crate::ContractError;
 use std::ops::Mul;
@@ -6,7 +6,10 @@ use std::ops::Mul;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{attr, from_slice, to_binary, Addr, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response, SubMsg, Uint128, WasmMsg};
-use cw2::set_contract_version;
+use cw_utils::Expiration;
+use cw2::{get_contract_version, set_contract_version};
+use stake_dragons_utils::migrate::stored_version;
+use stake_dragons_utils::ownership::{accept_ownership, cancel_ownership_proposal, propose_owner};
 use cw20::{Cw20Contract, Cw20ExecuteMsg, Cw20ReceiveMsg};
 
 const CONTRACT_NAME: &str = "crates.io:cw721-marketplace";
@@ -50,6 +53,24 @@ pub fn instantiate(
     Ok(res)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    stored_version(
+        &stored.contract,
+        &stored.version,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        None,
+    )?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -64,7 +85,6 @@ pub fn execute(
             execute_update_price(deps, env, info, token, price)
         }
         ExecuteMsg::UpdateConfig {
-            admin,
             nft_addr,
             allowed_native,
             allowed_cw20,
@@ -74,7 +94,6 @@ pub fn execute(
             deps,
             env,
             info,
-            admin,
             nft_addr,
             allowed_native,
             allowed_cw20,
@@ -82,6 +101,11 @@ pub fn execute(
             collector_addr,
         ),
         ExecuteMsg::Receive(cw20_receive_msg) => execute_receive(deps, env, info, cw20_receive_msg),
+        ExecuteMsg::ProposeOwner { new_owner, expires } => {
+            execute_propose_owner(deps, env, info, new_owner, expires)
+        }
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelOwnershipProposal {} => execute_cancel_ownership_proposal(deps, info),
     }
 }
 
@@ -192,7 +216,6 @@ pub fn execute_update_config(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    admin: Option<String>,
     nft_addr: Option<String>,
     allowed_native: Option<String>,
     allowed_cw20: Option<String>,
@@ -204,9 +227,6 @@ pub fn execute_update_config(
         return Err(ContractError::Unauthorized {});
     }
 
-    if let Some(admin) = admin {
-        cfg.admin = deps.api.addr_validate(&admin)?
-    }
     if let Some(nft_addr) = nft_addr {
         cfg.nft_contract_addr = deps.api.addr_validate(&nft_addr)?
     }
@@ -230,6 +250,51 @@ pub fn execute_update_config(
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
+pub fn execute_propose_owner(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_owner: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if cfg.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    let pending = propose_owner(deps.storage, &env.block, new_owner, expires)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_owner"),
+        attr("pending_owner", pending.owner),
+    ]))
+}
+
+pub fn execute_accept_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut cfg = CONFIG.load(deps.storage)?;
+    cfg.admin = accept_ownership(deps.storage, &env.block, &info.sender)?;
+    CONFIG.save(deps.storage, &cfg)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "accept_ownership"),
+        attr("admin", cfg.admin),
+    ]))
+}
+
+pub fn execute_cancel_ownership_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if cfg.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    cancel_ownership_proposal(deps.storage)?;
+    Ok(Response::new().add_attribute("action", "cancel_ownership_proposal"))
+}
+
 pub fn execute_receive(
     deps: DepsMut,
     _env: Env,
@@ -1,6 +1,7 @@
 use crate::state::{Config, Token};
 
 use cosmwasm_std::{Decimal, Uint128};
+use cw_utils::Expiration;
 use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -16,6 +17,10 @@ pub struct InstantiateMsg {
     pub collector_addr: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
@@ -32,7 +37,6 @@ pub enum ExecuteMsg {
         price: Uint128,
     },
     UpdateConfig {
-        admin: Option<String>,
         nft_addr: Option<String>,
         allowed_native: Option<String>,
         allowed_cw20: Option<String>,
@@ -40,6 +44,14 @@ pub enum ExecuteMsg {
         collector_addr: Option<String>,
     },
     Receive(Cw20ReceiveMsg),
+    /// Starts a two-step handover of the admin role, completed by `AcceptOwnership` from
+    /// `new_owner`.
+    ProposeOwner {
+        new_owner: String,
+        expires: Option<Expiration>,
+    },
+    AcceptOwnership {},
+    CancelOwnershipProposal {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -52,6 +64,7 @@ pub enum ReceiveMsg {
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    PendingOwner {},
     Token {
         id: String,
     },
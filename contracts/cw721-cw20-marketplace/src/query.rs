@@ -5,6 +5,7 @@ use crate::msg::{ConfigResponse, FloorPriceResponse, QueryMsg, TokenResponse, To
 use crate::state::{token_map, Token, CONFIG, ON_SALE};
 use cosmwasm_std::{to_binary, Binary, Deps, Env, Order, StdResult, Uint128};
 use cw_storage_plus::Bound;
+use stake_dragons_utils::ownership::query_pending_owner;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
@@ -12,6 +13,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Config {} => to_binary(&ConfigResponse {
             config: CONFIG.load(deps.storage)?,
         }),
+        QueryMsg::PendingOwner {} => to_binary(&query_pending_owner(deps.storage)?),
         QueryMsg::Token { id } => to_binary(&TokenResponse {
             token: token_map().load(deps.storage, id)?,
         }),
@@ -0,0 +1,131 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{from_binary, Decimal, Deps, DepsMut, Env, Response};
+use cw721_cw20_marketplace::execute::{execute, instantiate};
+use cw721_cw20_marketplace::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use cw721_cw20_marketplace::query::query;
+use cw721_cw20_marketplace::ContractError;
+use cw_utils::Expiration;
+use stake_dragons_utils::ownership::{OwnershipError, PendingOwnerResponse};
+
+const ADMIN: &str = "admin";
+
+fn setup_contract(deps: DepsMut) {
+    let msg = InstantiateMsg {
+        admin: ADMIN.to_string(),
+        nft_addr: "dragons".to_string(),
+        allowed_native: Some("ujuno".to_string()),
+        allowed_cw20: None,
+        fee_percentage: Decimal::percent(1),
+        collector_addr: "collector".to_string(),
+    };
+    instantiate(deps, mock_env(), mock_info(ADMIN, &[]), msg).unwrap();
+}
+
+fn execute_as(
+    deps: DepsMut,
+    env: Env,
+    sender: &str,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    execute(deps, env, mock_info(sender, &[]), msg)
+}
+
+fn query_admin(deps: Deps) -> String {
+    let res: ConfigResponse =
+        from_binary(&query(deps, mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    res.config.admin.to_string()
+}
+
+fn query_pending_owner(deps: Deps) -> PendingOwnerResponse {
+    from_binary(&query(deps, mock_env(), QueryMsg::PendingOwner {}).unwrap()).unwrap()
+}
+
+#[test]
+fn admin_handover_requires_acceptance() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    let propose = |expires: Option<Expiration>| ExecuteMsg::ProposeOwner {
+        new_owner: "new_admin".to_string(),
+        expires,
+    };
+
+    let err = execute_as(deps.as_mut(), mock_env(), "alice", propose(None)).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute_as(deps.as_mut(), mock_env(), ADMIN, propose(None)).unwrap();
+    assert_eq!(
+        query_pending_owner(deps.as_ref()).pending_owner,
+        Some("new_admin".to_string())
+    );
+    let err = execute_as(
+        deps.as_mut(),
+        mock_env(),
+        "mallory",
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Ownership(OwnershipError::Unauthorized {})
+    );
+    assert_eq!(query_admin(deps.as_ref()), ADMIN);
+
+    execute_as(
+        deps.as_mut(),
+        mock_env(),
+        "new_admin",
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap();
+    assert_eq!(query_admin(deps.as_ref()), "new_admin");
+    assert_eq!(query_pending_owner(deps.as_ref()).pending_owner, None);
+}
+
+#[test]
+fn admin_proposal_can_expire_or_be_cancelled() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    let deadline = Expiration::AtHeight(mock_env().block.height + 10);
+    let propose = ExecuteMsg::ProposeOwner {
+        new_owner: "new_admin".to_string(),
+        expires: Some(deadline),
+    };
+    execute_as(deps.as_mut(), mock_env(), ADMIN, propose).unwrap();
+    assert_eq!(query_pending_owner(deps.as_ref()).expires, Some(deadline));
+
+    let mut late = mock_env();
+    late.block.height += 10;
+    let err = execute_as(
+        deps.as_mut(),
+        late,
+        "new_admin",
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Ownership(OwnershipError::OwnershipProposalExpired {})
+    );
+
+    let cancel = ExecuteMsg::CancelOwnershipProposal {};
+    let err = execute_as(deps.as_mut(), mock_env(), "new_admin", cancel.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute_as(deps.as_mut(), mock_env(), ADMIN, cancel.clone()).unwrap();
+    let err = execute_as(
+        deps.as_mut(),
+        mock_env(),
+        "new_admin",
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Ownership(OwnershipError::NoPendingOwner {})
+    );
+    let err = execute_as(deps.as_mut(), mock_env(), ADMIN, cancel).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Ownership(OwnershipError::NoPendingOwner {})
+    );
+    assert_eq!(query_admin(deps.as_ref()), ADMIN);
+}
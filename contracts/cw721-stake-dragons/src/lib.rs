@@ -28,17 +28,21 @@ pub struct Metadata {
 
 pub type Extension = Option<Metadata>;
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MigrateMsg {}
+
 pub type Cw721StakeDragonsContract<'a> = cw721_base::Cw721Contract<'a, Extension, Empty>;
 pub type ExecuteMsg = cw721_base::ExecuteMsg<Extension>;
 
 #[cfg(not(feature = "library"))]
 pub mod entry {
 
-    use crate::{Cw721StakeDragonsContract, ExecuteMsg};
+    use crate::{Cw721StakeDragonsContract, ExecuteMsg, MigrateMsg};
     use cosmwasm_std::{entry_point, StdError};
     use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
-    use cw2::set_contract_version;
+    use cw2::{get_contract_version, set_contract_version};
     use cw721_base::{ContractError, InstantiateMsg, QueryMsg};
+    use stake_dragons_utils::migrate::{stored_version, MigrateError};
 
     // This is a simple type to let us handle empty extensions
 
@@ -73,6 +77,24 @@ pub mod entry {
         Cw721StakeDragonsContract::default().execute(deps, env, info, msg)
     }
 
+    #[entry_point]
+    pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, MigrateError> {
+        let stored = get_contract_version(deps.storage)?;
+        stored_version(
+            &stored.contract,
+            &stored.version,
+            CONTRACT_NAME,
+            CONTRACT_VERSION,
+            None,
+        )?;
+
+        set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+        Ok(Response::new()
+            .add_attribute("method", "migrate")
+            .add_attribute("from_version", stored.version)
+            .add_attribute("to_version", CONTRACT_VERSION))
+    }
+
     #[entry_point]
     pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         Cw721StakeDragonsContract::default().query(deps, env, msg)
@@ -84,15 +106,18 @@ mod tests {
     use super::*;
 
     use crate::ExecuteMsg;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
     use cosmwasm_std::{to_binary, Addr, CosmosMsg, StdError, WasmMsg};
     use cw721::NftInfoResponse;
     use cw721_base::helpers::Cw721Contract;
     use cw_multi_test::{App, BasicApp, Contract, ContractWrapper, Executor};
+    use stake_dragons_utils::migrate::MigrateError;
 
     const CREATOR: &str = "creator";
 
     pub fn contract_cw721_stake_dragons() -> Box<dyn Contract<Empty>> {
-        let contract = ContractWrapper::new(entry::execute, entry::instantiate, entry::query);
+        let contract = ContractWrapper::new(entry::execute, entry::instantiate, entry::query)
+            .with_migrate(entry::migrate);
         Box::new(contract)
     }
 
@@ -171,4 +196,69 @@ mod tests {
             StdError::generic_err("Operation not allowed").to_string()
         );
     }
+
+    #[test]
+    fn migrate_requires_admin_and_keeps_contract_name() {
+        let (mut app, contract, _) = init();
+        let code_id = app.store_code(contract_cw721_stake_dragons());
+        app.migrate_contract(
+            Addr::unchecked(CREATOR),
+            contract.addr(),
+            &MigrateMsg {},
+            code_id,
+        )
+        .unwrap_err();
+
+        // only the admin set on instantiate may migrate, so use a fresh instance with one
+        let init_msg = InstantiateMsg {
+            name: "SpaceShips".to_string(),
+            symbol: "SPACE".to_string(),
+            minter: CREATOR.to_string(),
+        };
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(CREATOR),
+                &init_msg,
+                &[],
+                "stake_dragons",
+                Some(CREATOR.to_string()),
+            )
+            .unwrap();
+        app.migrate_contract(
+            Addr::unchecked(CREATOR),
+            contract_addr.clone(),
+            &MigrateMsg {},
+            code_id,
+        )
+        .unwrap();
+        let version = cw2::query_contract_info(&app, contract_addr).unwrap();
+        assert_eq!(version.contract, "crates.io:cw721-stake-dragons");
+    }
+
+    #[test]
+    fn migrate_rejects_other_contracts_and_downgrades() {
+        let mut deps = mock_dependencies();
+        let init_msg = InstantiateMsg {
+            name: "SpaceShips".to_string(),
+            symbol: "SPACE".to_string(),
+            minter: CREATOR.to_string(),
+        };
+        entry::instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), init_msg).unwrap();
+
+        cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw721-base", "0.13.2").unwrap();
+        let err = entry::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(
+            matches!(err, MigrateError::WrongContract { contract } if contract == "crates.io:cw721-base")
+        );
+
+        cw2::set_contract_version(
+            deps.as_mut().storage,
+            "crates.io:cw721-stake-dragons",
+            "99.0.0",
+        )
+        .unwrap();
+        let err = entry::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(err, MigrateError::CannotDowngrade { .. }));
+    }
 }
@@ -1,30 +1,40 @@
 use crate::error::ContractError;
-use crate::msg::{
-    CollectionInfoResponse, CustomMintMsg, ExecuteMsg, Extension, InstantiateMsg, QueryMsg, ClaimMessage, Claim, StateResponse
-};
-use crate::state::{
-    CollectionInfo, State, Dragon, DragonListResponse, DragonResponse, COLLECTION_INFO, DRAGON_INFO,
-    DRAGON_INFO_SEQ, STATE, MIN_STAKE_TIME,
-};
+use crate::msg::{CollectionInfoResponse, CustomMintMsg, ExecuteMsg, Extension, InstantiateMsg, QueryMsg, ClaimMessage, Claim, StateResponse, KindRewardSchedule, MigrateMsg, RewardSchedulesResponse, BreedingFeeResponse, EmissionsResponse, KindEmissionWeight, GroupPause, PauseStatusResponse, OwedResponse, KindCount, PortfolioResponse, UnstakingDragon, AuthorizedMintersResponse, ClaimHistoryResponse, StakedAtResponse, TotalStakedAtResponse, VotingPowerAtHeightResponse, TotalPowerAtHeightResponse, KindVotingWeight, VotingWeightsResponse};
+use crate::state::{CollectionInfo, State, Dragon, DragonListResponse, DragonResponse, RewardSchedule, COLLECTION_INFO, DRAGON_INFO_SEQ, STATE, MIN_STAKE_TIME, MAX_REWARD_PRECISION, REWARD_SCHEDULES, dragon_info, BreedingConsent, BREEDING_CONSENTS, BREEDING_FEE, BREEDING_SEQ, PLANT_SEQ, LevelConfig, LEVEL_CONFIG, EmissionConfig, EMISSION_CONFIG, EMISSION_WEIGHTS, PauseGroup, PauseInfo, GUARDIAN, PAUSES, OWED, PENDING_CLAIMS, MINTERS, claim_history, DRAGON_CLAIM_TOTALS, OWNER_CLAIM_TOTALS, TOTAL_STAKED, VOTING_WEIGHTS};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::WasmMsg::Execute;
-use cosmwasm_std::{to_binary, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response, StdError, StdResult, Uint64, Uint128, SubMsg};
-use cw2::set_contract_version;
+use cosmwasm_std::{to_binary, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResult, Uint64, Uint128};
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
+use std::collections::BTreeMap;
 use std::ops::Add;
 use std::ptr::null;
 
 pub type Cw721Contract<'a> = cw721_base::Cw721Contract<'a, Extension, Empty>;
 pub type Cw721ExecuteMsg = cw721_base::ExecuteMsg<Extension>;
-use crate::helper::generate_dragon_birth_msg;
+use crate::helper::{generate_breeding_birth_msg, generate_dragon_birth_msg};
+use crate::emission::{self, accrued_state, update_emissions};
+use crate::pause::{active_pause, assert_not_paused};
+use crate::snapshot;
+use crate::reward::{calculate_reward, dragon_level, pay_reward, take_reward, DragonLevelResponse, RewardBreakdown};
+use crate::traits::{DragonTraits, Kind};
 use cw_storage_plus::Bound;
-use cw_utils::Expiration;
+use cw_utils::{must_pay, nonpayable, Expiration};
 use schemars::_serde_json::Value;
 use schemars::_serde_json::Value::Null;
+use stake_dragons_utils::migrate::stored_version;
+use stake_dragons_utils::ownership::{
+    accept_ownership, cancel_ownership_proposal, propose_owner, query_pending_owner,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:dragon-mint";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const LEGACY_CONTRACT_NAME: &str = "crates.io:cw721-base";
+
+const CLAIM_REPLY_ID: u64 = 1;
+const PENALTY_REPLY_ID: u64 = 2;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -32,15 +42,17 @@ pub fn instantiate(
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
-    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-
+) -> Result<Response, ContractError> {
     let minter = deps.api.addr_validate(&msg.base.minter)?;
     Cw721Contract::default().instantiate(deps.branch(), env, info.clone(), msg.base.clone())?;
+    // cw721-base records its own version, so ours has to be written afterwards
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     let state = State {
-        owner: String::from(info.sender.clone()),
+        owner: info.sender.clone(),
         reward_contract_address: msg.reward_contract_address,
+        treasury: None,
+        emergency_unstake_penalty: None,
     };
 
     let collection_info = CollectionInfo {
@@ -56,6 +68,14 @@ pub fn instantiate(
     COLLECTION_INFO.save(deps.storage, &collection_info)?;
     DRAGON_INFO_SEQ.save(deps.storage, &Uint64::zero())?;
     MIN_STAKE_TIME.save(deps.storage, &Uint64::new(1209600))?;
+    if let Some(fee) = msg.breeding_fee {
+        BREEDING_FEE.save(deps.storage, &fee)?;
+    }
+    BREEDING_SEQ.save(deps.storage, &0)?;
+    for KindRewardSchedule { kind, schedule } in msg.reward_schedules {
+        validate_reward_schedule(&kind, &schedule)?;
+        REWARD_SCHEDULES.save(deps.storage, &kind, &schedule)?;
+    }
     Ok(Response::new().add_attribute("method", "instantiate"))
 }
 
@@ -66,18 +86,72 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, &env.block, &msg)?;
     match msg {
-        ExecuteMsg::UpdateOwner {new_owner} => execute_update_owner(deps, info, new_owner),
+        ExecuteMsg::ProposeOwner { new_owner, expires } => {
+            execute_propose_owner(deps, env, info, new_owner, expires)
+        }
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelOwnershipProposal {} => execute_cancel_ownership_proposal(deps, info),
         ExecuteMsg::UpdateRewardContractAddress {new_address} => execute_update_reward_contract_address(deps, info, new_address),
         ExecuteMsg::UpdateMinStakeTime {time} => execute_update_min_stake_time(deps, info, time),
+        ExecuteMsg::UpdateTreasury { treasury } => execute_update_treasury(deps, info, treasury),
+        ExecuteMsg::UpdateEmergencyUnstakePenalty { penalty } => {
+            execute_update_emergency_unstake_penalty(deps, info, penalty)
+        }
+        ExecuteMsg::SetRewardSchedule { kind, schedule } => {
+            execute_set_reward_schedule(deps, info, kind, schedule)
+        }
+        ExecuteMsg::RemoveRewardSchedule { kind } => {
+            execute_remove_reward_schedule(deps, info, kind)
+        }
+        ExecuteMsg::UpdateLevelConfig { config } => execute_update_level_config(deps, info, config),
+        ExecuteMsg::UpdateEmissionConfig { config } => {
+            execute_update_emission_config(deps, info, env, config)
+        }
+        ExecuteMsg::SetEmissionWeight { kind, weight } => {
+            execute_set_emission_weight(deps, env, info, kind, weight)
+        }
+        ExecuteMsg::SetVotingWeight { kind, weight } => {
+            execute_set_voting_weight(deps, env, info, kind, weight)
+        }
+        ExecuteMsg::UpdateGuardian { guardian } => execute_update_guardian(deps, info, guardian),
+        ExecuteMsg::AddMinter { address } => execute_add_minter(deps, info, address),
+        ExecuteMsg::RemoveMinter { address } => execute_remove_minter(deps, info, address),
+        ExecuteMsg::Pause {
+            groups,
+            reason,
+            expires,
+        } => execute_pause(deps, info, groups, reason, expires),
+        ExecuteMsg::Unpause { groups } => execute_unpause(deps, info, groups),
         ExecuteMsg::Mint(msg) => execute_mint(deps, env, info, msg),
         ExecuteMsg::PlantEgg { token_id } => execute_plant_egg(deps, info, env, token_id),
+        ExecuteMsg::BreedDragons {
+            token_id,
+            partner_id,
+        } => execute_breed_dragons(deps, info, env, token_id, partner_id),
+        ExecuteMsg::ApproveBreeding {
+            token_id,
+            partner_id,
+        } => execute_approve_breeding(deps, info, token_id, partner_id),
+        ExecuteMsg::RevokeBreeding { token_id } => execute_revoke_breeding(deps, info, token_id),
+        ExecuteMsg::UpdateBreedingFee { fee } => execute_update_breeding_fee(deps, info, fee),
         ExecuteMsg::StakeDragon { token_id } => execute_stake_dragon(deps, info, env, token_id),
         ExecuteMsg::StartUnstakingProcess { token_id } => {
             execute_start_unstake_process(deps, info, env, token_id)
         }
+        ExecuteMsg::CancelUnstaking { token_id } => {
+            execute_cancel_unstaking(deps, info, env, token_id)
+        }
         ExecuteMsg::UnstakeDragon { token_id } => execute_unstake_dragon(deps, info, env, token_id),
+        ExecuteMsg::EmergencyUnstake { token_id } => {
+            execute_emergency_unstake(deps, info, env, token_id)
+        }
         ExecuteMsg::ClaimReward { token_id } => execute_claim_reward(deps, info, env, token_id),
+        ExecuteMsg::ClaimAllRewards { start_after, limit } => {
+            execute_claim_all_rewards(deps, info, env, start_after, limit)
+        }
+        ExecuteMsg::WithdrawOwed {} => execute_withdraw_owed(deps, info),
         ExecuteMsg::Claim { token_id } => execute_claim(deps, info, env, token_id),
         ExecuteMsg::Burn { token_id } => execute_burn(deps, env, info, token_id),
         ExecuteMsg::TransferNft {
@@ -104,18 +178,47 @@ pub fn execute(
     }
 }
 
-fn execute_update_owner(
+fn execute_propose_owner(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     new_owner: String,
+    expires: Option<Expiration>,
 ) -> Result<Response, ContractError> {
-    let mut state = STATE.load(deps.storage)?;
-    if info.sender.to_string() != state.owner {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
         return Err(ContractError::Unauthorized {});
     }
-    state.owner = new_owner;
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    let pending = propose_owner(deps.storage, &env.block, new_owner, expires)?;
+    Ok(Response::default()
+        .add_attribute("action", "propose_owner")
+        .add_attribute("pending_owner", pending.owner))
+}
+
+fn execute_accept_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    state.owner = accept_ownership(deps.storage, &env.block, &info.sender)?;
     STATE.save(deps.storage, &state)?;
-    Ok(Response::default().add_attribute("new_owner", state.owner))
+    Ok(Response::default()
+        .add_attribute("action", "accept_ownership")
+        .add_attribute("new_owner", state.owner))
+}
+
+fn execute_cancel_ownership_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    cancel_ownership_proposal(deps.storage)?;
+    Ok(Response::default().add_attribute("action", "cancel_ownership_proposal"))
 }
 
 fn execute_update_reward_contract_address(
@@ -124,7 +227,7 @@ fn execute_update_reward_contract_address(
     new_address: String,
 ) -> Result<Response, ContractError> {
     let mut state = STATE.load(deps.storage)?;
-    if info.sender.to_string() != state.owner {
+    if info.sender != state.owner {
         return Err(ContractError::Unauthorized {});
     }
     state.reward_contract_address = new_address;
@@ -138,7 +241,7 @@ fn execute_update_min_stake_time(
     time: Uint64,
 ) -> Result<Response, ContractError> {
     let state = STATE.load(deps.storage)?;
-    if info.sender.to_string() != state.owner {
+    if info.sender != state.owner {
         return Err(ContractError::Unauthorized {})
     }
     MIN_STAKE_TIME.update::<_, StdError>(deps.storage, |min_stake_time| Ok(time))?;
@@ -146,17 +249,360 @@ fn execute_update_min_stake_time(
     Ok(Response::default().add_attribute("min_stake_time", time))
 }
 
-fn execute_burn(
+fn execute_update_treasury(
+    deps: DepsMut,
+    info: MessageInfo,
+    treasury: String,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let treasury = deps.api.addr_validate(&treasury)?;
+    state.treasury = Some(treasury.clone());
+    STATE.save(deps.storage, &state)?;
+    Ok(Response::default()
+        .add_attribute("action", "update_treasury")
+        .add_attribute("treasury", treasury))
+}
+
+fn execute_update_emergency_unstake_penalty(
+    deps: DepsMut,
+    info: MessageInfo,
+    penalty: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if matches!(penalty, Some(penalty) if penalty > Decimal::one()) {
+        return Err(ContractError::InvalidEmergencyUnstakePenalty {});
+    }
+    state.emergency_unstake_penalty = penalty;
+    STATE.save(deps.storage, &state)?;
+    Ok(Response::default()
+        .add_attribute("action", "update_emergency_unstake_penalty")
+        .add_attribute(
+            "penalty",
+            penalty.map_or_else(|| "none".to_string(), |penalty| penalty.to_string()),
+        ))
+}
+
+fn validate_reward_schedule(kind: &str, schedule: &RewardSchedule) -> Result<(), ContractError> {
+    if kind.parse::<Kind>().is_err() {
+        return Err(ContractError::InvalidRewardSchedule {
+            reason: format!("invalid kind {:?}", kind),
+        });
+    }
+    if schedule.precision > MAX_REWARD_PRECISION {
+        return Err(ContractError::InvalidRewardSchedule {
+            reason: format!("precision cannot exceed {}", MAX_REWARD_PRECISION),
+        });
+    }
+    Ok(())
+}
+
+fn execute_set_reward_schedule(
+    deps: DepsMut,
+    info: MessageInfo,
+    kind: String,
+    schedule: RewardSchedule,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    validate_reward_schedule(&kind, &schedule)?;
+    REWARD_SCHEDULES.save(deps.storage, &kind, &schedule)?;
+    Ok(Response::default()
+        .add_attribute("action", "set_reward_schedule")
+        .add_attribute("kind", kind)
+        .add_attribute("rate", schedule.rate)
+        .add_attribute("precision", schedule.precision.to_string()))
+}
+
+fn execute_remove_reward_schedule(
+    deps: DepsMut,
+    info: MessageInfo,
+    kind: String,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !REWARD_SCHEDULES.has(deps.storage, &kind) {
+        return Err(ContractError::KindNotFound {});
+    }
+    // dragons of this kind would be left without a way to earn or claim
+    let in_use = dragon_info()
+        .idx
+        .kind
+        .prefix(kind.clone())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    if in_use {
+        return Err(ContractError::KindInUse { kind });
+    }
+    REWARD_SCHEDULES.remove(deps.storage, &kind);
+    Ok(Response::default()
+        .add_attribute("action", "remove_reward_schedule")
+        .add_attribute("kind", kind))
+}
+
+fn validate_level_config(config: &LevelConfig) -> Result<(), ContractError> {
+    if config.unstake_xp_decay > Decimal::one() {
+        return Err(ContractError::InvalidLevelConfig {
+            reason: "unstake xp decay cannot exceed 1".to_string(),
+        });
+    }
+    if config.thresholds.windows(2).any(|pair| pair[0].xp >= pair[1].xp) {
+        return Err(ContractError::InvalidLevelConfig {
+            reason: "thresholds must be in ascending xp order".to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn execute_update_level_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: LevelConfig,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    validate_level_config(&config)?;
+    LEVEL_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::default()
+        .add_attribute("action", "update_level_config")
+        .add_attribute("levels", config.thresholds.len().to_string())
+        .add_attribute("unstake_xp_decay", config.unstake_xp_decay.to_string()))
+}
+
+fn execute_update_emission_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    config: EmissionConfig,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // close the period emitted under the previous config first
+    update_emissions(deps.storage, env.block.time.seconds())?;
+    EMISSION_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::default()
+        .add_attribute("action", "update_emission_config")
+        .add_attribute("enabled", config.enabled.to_string())
+        .add_attribute("tokens_per_second", config.tokens_per_second))
+}
+
+/// Staked dragons of the kind are settled and earn at the new weight from now on.
+fn execute_set_emission_weight(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    kind: String,
+    weight: Uint128,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !REWARD_SCHEDULES.has(deps.storage, &kind) {
+        return Err(ContractError::KindNotFound {});
+    }
+    emission::reweight(deps.storage, &kind, weight, env.block.time.seconds())?;
+    Ok(Response::default()
+        .add_attribute("action", "set_emission_weight")
+        .add_attribute("kind", kind)
+        .add_attribute("weight", weight))
+}
+
+fn execute_set_voting_weight(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    kind: String,
+    weight: Uint128,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !REWARD_SCHEDULES.has(deps.storage, &kind) {
+        return Err(ContractError::KindNotFound {});
+    }
+    VOTING_WEIGHTS.save(deps.storage, &kind, &weight, env.block.height)?;
+    Ok(Response::default()
+        .add_attribute("action", "set_voting_weight")
+        .add_attribute("kind", kind)
+        .add_attribute("weight", weight))
+}
+
+fn execute_update_guardian(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: Option<String>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let guardian = match guardian {
+        Some(guardian) => {
+            let guardian = deps.api.addr_validate(&guardian)?;
+            GUARDIAN.save(deps.storage, &guardian)?;
+            guardian.to_string()
+        }
+        None => {
+            GUARDIAN.remove(deps.storage);
+            "none".to_string()
+        }
+    };
+    Ok(Response::default()
+        .add_attribute("action", "update_guardian")
+        .add_attribute("guardian", guardian))
+}
+
+fn execute_add_minter(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let minter = deps.api.addr_validate(&address)?;
+    MINTERS.save(deps.storage, &minter, &Empty {})?;
+    Ok(Response::default()
+        .add_attribute("action", "add_minter")
+        .add_attribute("minter", minter))
+}
+
+fn execute_remove_minter(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let minter = deps.api.addr_validate(&address)?;
+    MINTERS.remove(deps.storage, &minter);
+    Ok(Response::default()
+        .add_attribute("action", "remove_minter")
+        .add_attribute("minter", minter))
+}
+
+fn assert_owner_or_guardian(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender == state.owner {
+        return Ok(());
+    }
+    match GUARDIAN.may_load(deps.storage)? {
+        Some(guardian) if info.sender == guardian => Ok(()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+fn execute_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    groups: Vec<PauseGroup>,
+    reason: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    assert_owner_or_guardian(deps.as_ref(), &info)?;
+    let pause = PauseInfo {
+        reason: reason.clone(),
+        expires,
+    };
+    for group in groups.iter() {
+        PAUSES.save(deps.storage, group.as_str(), &pause)?;
+    }
+    Ok(Response::default()
+        .add_attribute("action", "pause")
+        .add_attribute("groups", group_names(&groups))
+        .add_attribute("reason", reason))
+}
+
+fn execute_unpause(
+    deps: DepsMut,
+    info: MessageInfo,
+    groups: Vec<PauseGroup>,
+) -> Result<Response, ContractError> {
+    assert_owner_or_guardian(deps.as_ref(), &info)?;
+    for group in groups.iter() {
+        PAUSES.remove(deps.storage, group.as_str());
+    }
+    Ok(Response::default()
+        .add_attribute("action", "unpause")
+        .add_attribute("groups", group_names(&groups)))
+}
+
+fn group_names(groups: &[PauseGroup]) -> String {
+    groups
+        .iter()
+        .map(|group| group.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn execute_burn(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
     token_id: String,
 ) -> Result<Response, ContractError> {
+    let id = parse_token_id(&token_id)?;
+    let dragon = dragon_info().load(deps.storage, id)?;
+    if dragon.is_staked {
+        return Err(ContractError::StakedDragonCantBeBurned {});
+    }
     let msg = Cw721ExecuteMsg::Burn { token_id };
-    Cw721Contract::default()
-        .execute(deps, env, info, msg)
-        .unwrap();
-    Ok(Response::new())
+    Cw721Contract::default().execute(deps.branch(), env, info, msg)?;
+    dragon_info().remove(deps.storage, id)?;
+    Ok(Response::new()
+        .add_attribute("action", "burn")
+        .add_attribute("token_id", dragon.token_id)
+        .add_attribute("old owner", dragon.owner))
+}
+
+fn parse_token_id(token_id: &str) -> Result<u64, ContractError> {
+    token_id
+        .parse::<u64>()
+        .map_err(|e| ContractError::Std(StdError::parse_err("u64", e.to_string())))
+}
+
+/// Moves a dragon record to its new cw721 owner. cw721-base checks that the sender
+/// is the owner or an approved spender/operator before the record is touched.
+fn transfer_dragon(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: u64,
+    recipient: String,
+    msg: Cw721ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let mut dragon = dragon_info().load(deps.storage, token_id)?;
+    if dragon.is_staked {
+        return Err(ContractError::StakedDragonCantBeTransferred {});
+    }
+    let valid_recipient = deps.api.addr_validate(&recipient)?;
+    let res = Cw721Contract::default().execute(deps.branch(), env, info, msg)?;
+    let old_owner = dragon.owner;
+    dragon.owner = valid_recipient.to_string();
+    dragon_info().save(deps.storage, token_id, &dragon)?;
+
+    Ok(res
+        .add_attribute("old owner", old_owner)
+        .add_attribute("new owner", dragon.owner))
 }
 
 fn execute_mint(
@@ -165,29 +611,28 @@ fn execute_mint(
     info: MessageInfo,
     mut msg: CustomMintMsg,
 ) -> Result<Response, ContractError> {
-    let mut kind = String::new();
-    let mut ovulation_period: u64 = 0;
-    let mut daily_income: String = String::from("");
-    for item in &msg.extension {
-        // iterate immutably
-        let trait_type: String = item.clone().trait_type;
-        let value: String = item.clone().value;
-
-        match &trait_type[..] {
-            "kind" => kind = value,
-            "ovulation_period" => ovulation_period = value.parse::<u64>().unwrap(),
-            "daily_income" => daily_income = value,
-            _ => return Err(ContractError::UnexpectedTraitType { trait_type }),
-        }
+    let cw721 = Cw721Contract::default();
+    // extra minters mint on behalf of the cw721 minter, which is the only one cw721-base accepts
+    let cw721_minter = cw721.minter.load(deps.storage)?;
+    if info.sender != cw721_minter && !MINTERS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let collection = COLLECTION_INFO.load(deps.storage)?;
+    if DRAGON_INFO_SEQ.load(deps.storage)? >= collection.size {
+        return Err(ContractError::InvalidSize {});
+    }
+    let traits = DragonTraits::from_traits(&msg.extension)?;
+    if !REWARD_SCHEDULES.has(deps.storage, traits.kind.as_str()) {
+        return Err(ContractError::KindNotFound {});
     }
     let id =
         DRAGON_INFO_SEQ.update::<_, StdError>(deps.storage, |id| Ok(id.add(Uint64::new(1))))?;
     let dragon = Dragon {
         token_id: id.to_string(),
         owner: msg.clone().base.owner,
-        kind,
-        ovulation_period,
-        daily_income,
+        kind: traits.kind.as_str().to_string(),
+        ovulation_period: traits.ovulation_period,
+        daily_income: traits.daily_income.to_string(),
         hatch: Uint64::zero(),
         is_staked: false,
         stake_start_time: Uint64::zero(),
@@ -195,13 +640,23 @@ fn execute_mint(
         unstaking_start_time: Uint64::zero(),
         unstaking_process: false,
         reward_end_time: Uint64::zero(),
+        xp: Uint64::zero(),
     };
-    DRAGON_INFO.save(deps.storage, id.u64(), &dragon)?;
+    dragon_info().save(deps.storage, id.u64(), &dragon)?;
     msg.base.token_id = id.to_string();
+    // the typed traits replace whatever the caller put under the same trait types
+    let mut metadata = msg.base.extension.take().unwrap_or_default();
+    metadata
+        .attributes
+        .retain(|attr| !matches!(attr.trait_type.as_str(), "kind" | "ovulation_period" | "daily_income"));
+    metadata.attributes.extend(traits.to_attributes());
+    msg.base.extension = Some(metadata);
     let mint_msg = Cw721ExecuteMsg::Mint(msg.base.clone());
-    Cw721Contract::default()
-        .execute(deps, env, info, mint_msg)
-        .unwrap();
+    let mint_info = MessageInfo {
+        sender: cw721_minter,
+        funds: info.funds,
+    };
+    cw721.execute(deps, env, mint_info, mint_msg)?;
     Ok(Response::default()
         .add_attribute("new owner", dragon.owner.clone())
         .add_attribute("dragon id", dragon.token_id)
@@ -226,24 +681,27 @@ fn execute_plant_egg(
     env: Env,
     token_id: Uint64,
 ) -> Result<Response, ContractError> {
-    let mut dragon = DRAGON_INFO.load(deps.storage, token_id.u64())?;
+    let mut dragon = dragon_info().load(deps.storage, token_id.u64())?;
     if dragon.owner != info.sender.to_string() {
         return Err(ContractError::Unauthorized {});
     }
     if dragon.unstaking_process {
         return Err(ContractError::OngoingUnstakingProcess {});
     }
-    if !dragon.hatch.is_zero() && dragon.hatch.u64() <= env.block.time.seconds() {
+    if dragon.is_fertile(env.block.time.seconds()) {
         dragon.hatch = Uint64::zero();
     } else {
         return Err(ContractError::OvulationInProgress {});
     }
-    DRAGON_INFO.save(deps.storage, token_id.u64(), &dragon)?;
+    dragon_info().save(deps.storage, token_id.u64(), &dragon)?;
 
+    let seq = PLANT_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    PLANT_SEQ.save(deps.storage, &seq)?;
     let collection = COLLECTION_INFO.load(deps.storage)?;
-    let msg = generate_dragon_birth_msg(token_id.to_string(), info.sender.to_string())?;
+    let msg = generate_dragon_birth_msg(seq, &dragon, info.sender.to_string())?;
     Ok(Response::default()
         .add_attribute("resetted hatch value", dragon.hatch)
+        .add_attribute("birth_id", msg.dragon_birth.id.clone())
         .add_message(CosmosMsg::Wasm(Execute {
             contract_addr: collection.minter,
             msg: to_binary(&msg)?,
@@ -251,6 +709,144 @@ fn execute_plant_egg(
         })))
 }
 
+fn execute_breed_dragons(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    token_id: Uint64,
+    partner_id: Uint64,
+) -> Result<Response, ContractError> {
+    if token_id == partner_id {
+        return Err(ContractError::SameParents {});
+    }
+    let now = env.block.time.seconds();
+    let mut dragon = dragon_info().load(deps.storage, token_id.u64())?;
+    let mut partner = dragon_info().load(deps.storage, partner_id.u64())?;
+    dragon.is_owner(info.sender.to_string())?;
+    let needs_consent = partner.owner != dragon.owner;
+    if needs_consent {
+        // consent is only valid while the partner keeps the same owner
+        let consent = BREEDING_CONSENTS.may_load(deps.storage, partner_id.u64())?;
+        if !matches!(consent, Some(c) if c.owner == partner.owner && c.partner_id == token_id.u64()) {
+            return Err(ContractError::BreedingConsentNotFound {
+                token_id: partner_id.to_string(),
+            });
+        }
+    }
+    for parent in [&dragon, &partner] {
+        if !parent.is_staked {
+            return Err(ContractError::DragonNotStaked {});
+        }
+        if parent.unstaking_process {
+            return Err(ContractError::OngoingUnstakingProcess {});
+        }
+        if !parent.is_fertile(now) {
+            return Err(ContractError::OvulationInProgress {});
+        }
+    }
+
+    let state = STATE.load(deps.storage)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    match BREEDING_FEE.may_load(deps.storage)? {
+        Some(fee) => {
+            if must_pay(&info, &fee.denom)? != fee.amount {
+                return Err(ContractError::InvalidBreedingFee { fee: fee.to_string() });
+            }
+            messages.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: state.owner.to_string(),
+                amount: vec![fee],
+            }));
+        }
+        None => {
+            nonpayable(&info)?;
+        }
+    }
+
+    if needs_consent {
+        BREEDING_CONSENTS.remove(deps.storage, partner_id.u64());
+    }
+    dragon.start_ovulation(now)?;
+    partner.start_ovulation(now)?;
+    dragon_info().save(deps.storage, token_id.u64(), &dragon)?;
+    dragon_info().save(deps.storage, partner_id.u64(), &partner)?;
+
+    let seq = BREEDING_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    BREEDING_SEQ.save(deps.storage, &seq)?;
+    let collection = COLLECTION_INFO.load(deps.storage)?;
+    let msg = generate_breeding_birth_msg(seq, dragon.owner.clone(), [&dragon, &partner])?;
+    messages.push(CosmosMsg::Wasm(Execute {
+        contract_addr: collection.minter,
+        msg: to_binary(&msg)?,
+        funds: vec![],
+    }));
+    Ok(Response::default()
+        .add_attribute("action", "breed_dragons")
+        .add_attribute("token_id", token_id)
+        .add_attribute("partner_id", partner_id)
+        .add_attribute("birth_id", msg.dragon_birth.id)
+        .add_messages(messages))
+}
+
+fn execute_approve_breeding(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: Uint64,
+    partner_id: Uint64,
+) -> Result<Response, ContractError> {
+    let dragon = dragon_info().load(deps.storage, token_id.u64())?;
+    dragon.is_owner(info.sender.to_string())?;
+    if token_id == partner_id {
+        return Err(ContractError::SameParents {});
+    }
+    let consent = BreedingConsent {
+        owner: dragon.owner,
+        partner_id: partner_id.u64(),
+    };
+    BREEDING_CONSENTS.save(deps.storage, token_id.u64(), &consent)?;
+    Ok(Response::default()
+        .add_attribute("action", "approve_breeding")
+        .add_attribute("token_id", token_id)
+        .add_attribute("partner_id", partner_id))
+}
+
+fn execute_revoke_breeding(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: Uint64,
+) -> Result<Response, ContractError> {
+    let dragon = dragon_info().load(deps.storage, token_id.u64())?;
+    dragon.is_owner(info.sender.to_string())?;
+    BREEDING_CONSENTS.remove(deps.storage, token_id.u64());
+    Ok(Response::default()
+        .add_attribute("action", "revoke_breeding")
+        .add_attribute("token_id", token_id))
+}
+
+fn execute_update_breeding_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee: Option<Coin>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let fee_attribute = match fee {
+        Some(fee) => {
+            let attribute = fee.to_string();
+            BREEDING_FEE.save(deps.storage, &fee)?;
+            attribute
+        }
+        None => {
+            BREEDING_FEE.remove(deps.storage);
+            "none".to_string()
+        }
+    };
+    Ok(Response::default()
+        .add_attribute("action", "update_breeding_fee")
+        .add_attribute("fee", fee_attribute))
+}
+
 fn execute_transfer_nft(
     deps: DepsMut,
     env: Env,
@@ -261,23 +857,8 @@ fn execute_transfer_nft(
     let msg = Cw721ExecuteMsg::TransferNft {
         recipient: recipient.clone(),
         token_id: token_id.to_string(),
-    };
-    let mut dragon = DRAGON_INFO.load(deps.storage, token_id.u64())?;
-    let is_staked = dragon.clone().is_staked;
-    if is_staked {
-        return Err(ContractError::StakedDragonCantBeTransferred {});
-    }
-    let _is_owner = dragon.clone().is_owner(info.sender.to_string())?;
-    let valid_recipient = deps.api.addr_validate(&*recipient)?;
-    dragon.owner = valid_recipient.to_string();
-    DRAGON_INFO.save(deps.storage, token_id.u64(), &dragon)?;
-    Cw721Contract::default()
-        .execute(deps, env, info.clone(), msg)
-        .unwrap();
-
-    Ok(Response::default()
-        .add_attribute("old owner", info.sender.to_string())
-        .add_attribute("new owner", dragon.owner))
+    };
+    transfer_dragon(deps, env, info, token_id.u64(), recipient, msg)
 }
 
 pub fn execute_stake_dragon(
@@ -286,7 +867,7 @@ pub fn execute_stake_dragon(
     env: Env,
     token_id: Uint64,
 ) -> Result<Response, ContractError> {
-    let mut dragon = DRAGON_INFO.load(deps.storage, token_id.u64())?;
+    let mut dragon = dragon_info().load(deps.storage, token_id.u64())?;
     if dragon.is_staked {
         return Err(ContractError::DragonAlreadyStaked {});
     }
@@ -295,15 +876,16 @@ pub fn execute_stake_dragon(
     let now = Uint64::new(env.block.time.seconds());
     dragon.stake_start_time = now;
     dragon.reward_start_time = now;
-    //get today as seconds
-    let today_in_seconds = Uint64::new(env.block.time.seconds());
-    //calculate the second that it will take using the ovulation period
-    //1 day -> 86400 seconds
-    let ovulation_period_in_seconds: Uint64 = Uint64::new(dragon.ovulation_period);
-    let total_seconds_to_add = ovulation_period_in_seconds.checked_mul(Uint64::new(86400))?;
-    let hatch_time: Uint64 = total_seconds_to_add.checked_add(Uint64::from(today_in_seconds))?;
-    dragon.hatch = hatch_time;
-    DRAGON_INFO.save(deps.storage, token_id.u64(), &dragon)?;
+    dragon.start_ovulation(env.block.time.seconds())?;
+    dragon_info().save(deps.storage, token_id.u64(), &dragon)?;
+    emission::join(deps.storage, token_id.u64(), &dragon.kind, env.block.time.seconds())?;
+    snapshot::record_stake(
+        deps.storage,
+        &dragon.owner,
+        &dragon.kind,
+        token_id.u64(),
+        env.block.height,
+    )?;
     Ok(Response::default()
         .add_attribute("token_id", dragon.token_id.to_string())
         .add_attribute("is_staked", dragon.is_staked.to_string())
@@ -319,7 +901,7 @@ fn execute_start_unstake_process(
     env: Env,
     token_id: Uint64,
 ) -> Result<Response, ContractError> {
-    let mut dragon = DRAGON_INFO.load(deps.storage, token_id.u64())?;
+    let mut dragon = dragon_info().load(deps.storage, token_id.u64())?;
     if dragon.unstaking_process {
         return Err(ContractError::OngoingUnstakingProcess {});
     }
@@ -332,20 +914,61 @@ fn execute_start_unstake_process(
     dragon.unstaking_process = true;
     dragon.unstaking_start_time = Uint64::new(env.block.time.seconds());
     dragon.reward_end_time = Uint64::new(env.block.time.seconds());
-    DRAGON_INFO.save(deps.storage, token_id.u64(), &dragon)?;
+    dragon_info().save(deps.storage, token_id.u64(), &dragon)?;
+    emission::leave(deps.storage, token_id.u64(), env.block.time.seconds())?;
     Ok(Response::default()
         .add_attribute("token_id", dragon.clone().token_id.to_string())
         .add_attribute("unstaking_start_time", dragon.unstaking_start_time)
         .add_attribute("reward_end_time", dragon.reward_end_time))
 }
 
+fn execute_cancel_unstaking(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    token_id: Uint64,
+) -> Result<Response, ContractError> {
+    let mut dragon = dragon_info().load(deps.storage, token_id.u64())?;
+    if !dragon.is_staked {
+        return Err(ContractError::DragonNotStaked {});
+    }
+    if dragon.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !dragon.unstaking_process {
+        return Err(ContractError::UnstakingProcessIsNotStarted {});
+    }
+    let now = Uint64::new(env.block.time.seconds());
+    // skip the time spent unstaking so rewards and xp accrued before it stay claimable;
+    // the hatch is untouched, so ovulation carries on
+    dragon.stake_start_time = dragon
+        .stake_start_time
+        .checked_add(now.checked_sub(dragon.unstaking_start_time)?)?;
+    dragon.reward_start_time = if dragon.reward_start_time.is_zero() {
+        now
+    } else {
+        dragon
+            .reward_start_time
+            .checked_add(now.checked_sub(dragon.reward_end_time)?)?
+    };
+    dragon.reward_end_time = Uint64::zero();
+    dragon.unstaking_process = false;
+    dragon.unstaking_start_time = Uint64::zero();
+    dragon_info().save(deps.storage, token_id.u64(), &dragon)?;
+    emission::join(deps.storage, token_id.u64(), &dragon.kind, now.u64())?;
+    Ok(Response::default()
+        .add_attribute("action", "cancel_unstaking")
+        .add_attribute("token_id", dragon.token_id)
+        .add_attribute("reward_start_time", dragon.reward_start_time))
+}
+
 fn execute_unstake_dragon(
     deps: DepsMut,
     info: MessageInfo,
     env: Env,
     token_id: Uint64,
 ) -> Result<Response, ContractError> {
-    let mut dragon = DRAGON_INFO.load(deps.storage, token_id.u64())?;
+    let mut dragon = dragon_info().load(deps.storage, token_id.u64())?;
     if !dragon.is_staked {
         return Err(ContractError::DragonNotStaked {});
     }
@@ -362,14 +985,7 @@ fn execute_unstake_dragon(
     {
         return Err(ContractError::MinUnstakingTimeRequired {});
     }
-    //Hatch
-    dragon.hatch = Uint64::zero();
-    dragon.is_staked = false;
-    dragon.stake_start_time = Uint64::zero();
-    dragon.reward_start_time = Uint64::zero();
-    dragon.unstaking_process = false;
-    dragon.unstaking_start_time = Uint64::zero();
-    DRAGON_INFO.save(deps.storage, token_id.u64(), &dragon)?;
+    release_dragon(deps.storage, &env.block, token_id.u64(), &mut dragon)?;
     Ok(Response::default()
         .add_attribute("token_id", dragon.clone().token_id.to_string())
         .add_attribute("is_staked", dragon.clone().is_staked.to_string())
@@ -388,7 +1004,87 @@ fn execute_unstake_dragon(
         .add_attribute(
             "unstaking_start_time",
             dragon.clone().unstaking_start_time.to_string(),
-        ))
+        )
+        .add_attribute("xp", dragon.xp))
+}
+
+fn execute_emergency_unstake(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    token_id: Uint64,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    let (treasury, penalty_rate) = match (state.treasury, state.emergency_unstake_penalty) {
+        (Some(treasury), Some(penalty_rate)) => (treasury, penalty_rate),
+        _ => return Err(ContractError::EmergencyUnstakeDisabled {}),
+    };
+    let mut dragon = dragon_info().load(deps.storage, token_id.u64())?;
+    if !dragon.is_staked {
+        return Err(ContractError::DragonNotStaked {});
+    }
+    if dragon.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let reward = pay_reward(deps.storage, &env.block, token_id.u64(), &mut dragon)?;
+    let penalty = reward * penalty_rate;
+    let payout = reward.checked_sub(penalty)?;
+    release_dragon(deps.storage, &env.block, token_id.u64(), &mut dragon)?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "emergency_unstake")
+        .add_attribute("token_id", dragon.token_id.clone())
+        .add_attribute("reward", reward)
+        .add_attribute("payout", payout)
+        .add_event(
+            Event::new("emergency_unstake_penalty")
+                .add_attribute("token_id", dragon.token_id)
+                .add_attribute("owner", info.sender.to_string())
+                .add_attribute("treasury", treasury.to_string())
+                .add_attribute("rate", penalty_rate.to_string())
+                .add_attribute("amount", penalty),
+        );
+    if !payout.is_zero() {
+        res = res.add_submessage(claim_submsg(
+            deps.storage,
+            CLAIM_REPLY_ID,
+            info.sender.to_string(),
+            payout,
+        )?);
+    }
+    if !penalty.is_zero() {
+        res = res.add_submessage(claim_submsg(
+            deps.storage,
+            PENALTY_REPLY_ID,
+            treasury.to_string(),
+            penalty,
+        )?);
+    }
+    Ok(res)
+}
+
+/// Takes a dragon out of staking, decaying its xp and dropping it from the emissions.
+fn release_dragon(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    token_id: u64,
+    dragon: &mut Dragon,
+) -> Result<(), ContractError> {
+    let now = block.time.seconds();
+    let level_config = LEVEL_CONFIG.may_load(storage)?.unwrap_or_default();
+    dragon.xp = Uint64::new(level_config.decayed_xp(dragon.current_xp(now)?));
+    //Hatch
+    dragon.hatch = Uint64::zero();
+    dragon.is_staked = false;
+    dragon.stake_start_time = Uint64::zero();
+    dragon.reward_start_time = Uint64::zero();
+    dragon.reward_end_time = Uint64::zero();
+    dragon.unstaking_process = false;
+    dragon.unstaking_start_time = Uint64::zero();
+    dragon_info().save(storage, token_id, dragon)?;
+    emission::remove(storage, token_id, now)?;
+    snapshot::record_unstake(storage, &dragon.owner, &dragon.kind, token_id, block.height)?;
+    Ok(())
 }
 
 fn execute_claim_reward(
@@ -397,62 +1093,141 @@ fn execute_claim_reward(
     env: Env,
     token_id: Uint64,
 ) -> Result<Response, ContractError> {
-    let mut dragon = DRAGON_INFO.load(deps.storage, token_id.u64())?;
+    let mut dragon = dragon_info().load(deps.storage, token_id.u64())?;
     if !dragon.is_staked {
         return Err(ContractError::DragonNotStaked {});
     }
     if dragon.owner != info.sender.to_string() {
         return Err(ContractError::Unauthorized {});
     }
-    //Reward calculation
-    let now = env.block.time.seconds();
-    let mut reward = Uint128::zero();
-    if dragon.unstaking_process {
-        if dragon.kind == "common".to_string() {
-            let second_difference = Uint128::new(dragon.reward_end_time.u64() as u128).checked_sub(Uint128::new(dragon.reward_start_time.u64() as u128))?;
-            let second_difference_multiplied = second_difference.checked_mul(Uint128::new(500000))?;
-            reward = second_difference_multiplied.checked_div(Uint128::new(86400)).unwrap_or(Uint128::zero());
-        } else {
-            let second_difference = Uint128::new(dragon.reward_end_time.u64() as u128).checked_sub(Uint128::new(dragon.reward_start_time.u64() as u128))?;
-            let second_difference_multiplied = second_difference.checked_mul(Uint128::new(1000000))?;
-            let second_difference_multiplied_daily_income = second_difference_multiplied.checked_mul(Uint128::new(dragon.daily_income.parse::<u128>().unwrap()))?;
-            reward = second_difference_multiplied_daily_income.checked_div(Uint128::new(86400)).unwrap_or(Uint128::zero());
-        }
-    } else {
-        if dragon.kind == "common".to_string() {
-            let second_difference = Uint128::new(now as u128).checked_sub(Uint128::new(dragon.reward_start_time.u64() as u128))?;
-            let second_difference_multiplied = second_difference.checked_mul(Uint128::new(500000))?;
-            reward = second_difference_multiplied.checked_div(Uint128::new(86400)).unwrap_or(Uint128::zero());
-        } else {
-            let second_difference = Uint128::new(now as u128).checked_sub(Uint128::new(dragon.reward_start_time.u64() as u128))?;
-            let second_difference_multiplied = second_difference.checked_mul(Uint128::new(1000000))?;
-            let second_difference_multiplied_daily_income = second_difference_multiplied.checked_mul(Uint128::new(dragon.daily_income.parse::<u128>().unwrap()))?;
-            reward = second_difference_multiplied_daily_income.checked_div(Uint128::new(86400)).unwrap_or(Uint128::zero());
+    let reward = pay_reward(deps.storage, &env.block, token_id.u64(), &mut dragon)?;
+    dragon_info().save(deps.storage, token_id.u64(), &dragon)?;
+    if reward.is_zero() {
+        return Ok(Response::new().add_attribute("amount", reward));
+    }
+    let claim_reward_msg = claim_submsg(deps.storage, CLAIM_REPLY_ID, info.sender.to_string(), reward)?;
+    Ok(Response::new().add_submessages(vec![claim_reward_msg]))
+}
+
+/// Builds the claim sent to the reward contract. The claim is kept until its reply arrives,
+/// so a failed payout is recorded as owed to the recipient instead of being lost.
+fn claim_submsg(
+    storage: &mut dyn Storage,
+    reply_id: u64,
+    recipient: String,
+    amount: Uint128,
+) -> Result<SubMsg, ContractError> {
+    let state = STATE.load(storage)?;
+    let claim = Claim { recipient, amount };
+    PENDING_CLAIMS.save(storage, reply_id, &claim)?;
+    let msg = ClaimMessage { claim };
+    Ok(SubMsg::reply_always(
+        CosmosMsg::Wasm(Execute {
+            contract_addr: state.reward_contract_address,
+            msg: to_binary(&msg)?,
+            funds: vec![],
+        }),
+        reply_id,
+    ))
+}
+
+fn execute_withdraw_owed(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let amount = OWED.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+    if amount.is_zero() {
+        return Err(ContractError::NothingOwed {
+            address: info.sender.to_string(),
+        });
+    }
+    OWED.remove(deps.storage, &info.sender);
+    let msg = claim_submsg(deps.storage, CLAIM_REPLY_ID, info.sender.to_string(), amount)?;
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_owed")
+        .add_attribute("amount", amount)
+        .add_submessage(msg))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        CLAIM_REPLY_ID | PENALTY_REPLY_ID => reply_claim(deps, msg.id, msg.result),
+        id => Err(ContractError::UnknownReplyId { id }),
+    }
+}
+
+fn reply_claim(deps: DepsMut, id: u64, result: SubMsgResult) -> Result<Response, ContractError> {
+    let claim = PENDING_CLAIMS.load(deps.storage, id)?;
+    PENDING_CLAIMS.remove(deps.storage, id);
+    match result {
+        SubMsgResult::Ok(_) => Ok(Response::new().add_attribute("claim", "paid")),
+        SubMsgResult::Err(err) => {
+            let recipient = deps.api.addr_validate(&claim.recipient)?;
+            let owed = OWED.update(deps.storage, &recipient, |owed| -> StdResult<_> {
+                Ok(owed.unwrap_or_default().checked_add(claim.amount)?)
+            })?;
+            Ok(Response::new()
+                .add_attribute("claim", "failed")
+                .add_attribute("error", err)
+                .add_attribute("recipient", recipient)
+                .add_attribute("owed", owed))
         }
     }
-    let state = STATE.load(deps.storage)?;
-    let msg = ClaimMessage {
-        claim: Claim {
-            recipient: info.sender.to_string(),
-            amount: reward,
+}
+
+fn execute_claim_all_rewards(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let owner = info.sender.to_string();
+    let dragons: Vec<(u64, Dragon)> = dragon_info()
+        .idx
+        .owner
+        .prefix(owner.clone())
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, dragon)) => dragon.is_staked,
+            Err(_) => true,
+        })
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+
+    let last_token_id = dragons.last().map(|(id, _)| *id);
+    let mut claimed_dragons = 0;
+    let mut skipped = vec![];
+    let mut total_reward = Uint128::zero();
+    for (id, mut dragon) in dragons {
+        // a dragon whose reward cannot be worked out is left as is instead of failing the page
+        match pay_reward(deps.storage, &env.block, id, &mut dragon) {
+            Ok(reward) => total_reward = total_reward.checked_add(reward)?,
+            Err(_) => {
+                skipped.push(id.to_string());
+                continue;
+            }
         }
-    };
+        dragon_info().save(deps.storage, id, &dragon)?;
+        claimed_dragons += 1;
+    }
 
-    let claim_reward_msg = CosmosMsg::Wasm(Execute {
-        contract_addr: state.reward_contract_address,
-        msg: to_binary(&msg)?,
-        funds: vec![],
-    });
-    if dragon.unstaking_process {
-        dragon.reward_start_time = Uint64::zero();
-        dragon.reward_end_time = Uint64::zero();
-    } else {
-        dragon.reward_start_time = Uint64::new(env.block.time.seconds());
+    let mut res = Response::new()
+        .add_attribute("action", "claim_all_rewards")
+        .add_attribute("claimed_dragons", claimed_dragons.to_string())
+        .add_attribute("amount", total_reward);
+    if !skipped.is_empty() {
+        res = res.add_attribute("skipped_dragons", skipped.join(","));
+    }
+    if let Some(last_token_id) = last_token_id {
+        res = res.add_attribute("last_token_id", last_token_id.to_string());
+    }
+    if total_reward.is_zero() {
+        return Ok(res);
     }
-    DRAGON_INFO.save(deps.storage, token_id.u64(), &dragon)?;
-    Ok(Response::new().add_submessages(vec![
-        SubMsg::new(claim_reward_msg),
-    ]))
+
+    let msg = claim_submsg(deps.storage, CLAIM_REPLY_ID, owner, total_reward)?;
+    Ok(res.add_submessage(msg))
 }
 
 fn execute_claim(
@@ -461,20 +1236,16 @@ fn execute_claim(
     env: Env,
     token_id: Uint64,
 ) -> Result<Response, ContractError> {
-    let mut dragon = DRAGON_INFO.load(deps.storage, token_id.u64())?;
+    let mut dragon = dragon_info().load(deps.storage, token_id.u64())?;
     if !dragon.is_staked {
         return Err(ContractError::DragonNotStaked {});
     }
     if dragon.owner != info.sender.to_string() {
         return Err(ContractError::Unauthorized {});
     }
-    if dragon.unstaking_process {
-        dragon.reward_start_time = Uint64::zero();
-        dragon.reward_end_time = Uint64::zero();
-    } else {
-        dragon.reward_start_time = Uint64::new(env.block.time.seconds());
-    }
-    DRAGON_INFO.save(deps.storage, token_id.u64(), &dragon)?;
+    // the accrued reward is forfeited
+    take_reward(deps.storage, token_id.u64(), &mut dragon, env.block.time.seconds())?;
+    dragon_info().save(deps.storage, token_id.u64(), &dragon)?;
     Ok(Response::new().add_attribute("reward_start_time", dragon.reward_start_time)
         .add_attribute("reward_end_time", dragon.reward_end_time))
 }
@@ -559,18 +1330,98 @@ fn execute_send_nft(
     token_id: String,
     msg: Binary,
 ) -> Result<Response, ContractError> {
+    let id = parse_token_id(&token_id)?;
     let send_msg = Cw721ExecuteMsg::SendNft {
-        contract,
+        contract: contract.clone(),
         token_id,
         msg,
     };
-    let res = Cw721Contract::default().execute(deps, env, info, send_msg);
-    if res.is_err() {
-        return Err(ContractError::NftContractError {
-            method: "send nft".to_string(),
-        });
+    transfer_dragon(deps, env, info, id, contract, send_msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    // v0.1 instances were left with the version written by cw721-base, which any cw721 shares,
+    // so that name only counts over dragon-mint's own state
+    let legacy = DRAGON_INFO_SEQ
+        .may_load(deps.storage)?
+        .map(|_| LEGACY_CONTRACT_NAME);
+    let stored_version = stored_version(
+        &stored.contract,
+        &stored.version,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        legacy,
+    )?;
+
+    for KindRewardSchedule { kind, schedule } in msg.reward_schedules {
+        validate_reward_schedule(&kind, &schedule)?;
+        REWARD_SCHEDULES.save(deps.storage, &kind, &schedule)?;
     }
-    Ok(Response::new())
+
+    let mut res = Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", stored.version);
+    if stored_version < Version::new(0, 2, 0) {
+        res = res.add_attributes(migrate_v0_1(deps.branch(), env.block.time.seconds())?);
+    }
+    if stored_version < Version::new(0, 3, 0) {
+        res = res.add_attributes(migrate_v0_2(deps.branch(), env.block.height)?);
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(res.add_attribute("to_version", CONTRACT_VERSION))
+}
+
+/// v0.1 stored the owner as an unchecked string, kept dragons in a non indexed map and paid
+/// fixed rates instead of reward schedules.
+fn migrate_v0_1(deps: DepsMut, now: u64) -> Result<Vec<(String, String)>, ContractError> {
+    let dragons: Vec<(u64, Dragon)> = dragon_info()
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    // without a schedule the dragon could never claim again
+    for (_, dragon) in dragons.iter() {
+        if !REWARD_SCHEDULES.has(deps.storage, &dragon.kind) {
+            return Err(ContractError::MissingRewardSchedule {
+                kind: dragon.kind.clone(),
+            });
+        }
+    }
+
+    let mut state = STATE.load(deps.storage)?;
+    state.owner = deps.api.addr_validate(state.owner.as_str())?;
+    STATE.save(deps.storage, &state)?;
+
+    // re-save every dragon so the owner, is_staked and kind indexes get written
+    for (id, dragon) in dragons.iter() {
+        dragon_info().save(deps.storage, *id, dragon)?;
+        // dragons staked before emissions existed start sharing them from now on
+        if dragon.is_staked && !dragon.unstaking_process {
+            emission::join(deps.storage, *id, &dragon.kind, now)?;
+        }
+    }
+    Ok(vec![(
+        "reindexed_dragons".to_string(),
+        dragons.len().to_string(),
+    )])
+}
+
+/// v0.2 kept no staking snapshots, dragons already staked are recorded from this height on.
+fn migrate_v0_2(deps: DepsMut, height: u64) -> Result<Vec<(String, String)>, ContractError> {
+    let staked: Vec<(u64, Dragon)> = dragon_info()
+        .idx
+        .is_staked
+        .prefix(1)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    TOTAL_STAKED.save(deps.storage, &0, height)?;
+    for (id, dragon) in staked.iter() {
+        snapshot::record_stake(deps.storage, &dragon.owner, &dragon.kind, *id, height)?;
+    }
+    Ok(vec![(
+        "snapshotted_dragons".to_string(),
+        staked.len().to_string(),
+    )])
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -585,47 +1436,221 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             start_after,
             limit,
             owner,
-        } => to_binary(&range_user_dragons(deps, start_after, limit, owner)?),
+            is_staked,
+        } => to_binary(&range_user_dragons(deps, start_after, limit, owner, is_staked)?),
+        QueryMsg::QueryUserDragon { owner } => to_binary(&query_user_dragons(deps, owner)?),
+        QueryMsg::RangeKindDragons {
+            start_after,
+            limit,
+            kind,
+        } => to_binary(&range_kind_dragons(deps, start_after, limit, kind)?),
+        QueryMsg::RangeStakedDragons {
+            start_after,
+            limit,
+            is_staked,
+        } => to_binary(&range_staked_dragons(deps, start_after, limit, is_staked)?),
         QueryMsg::CalculateReward { token_id } => {
             to_binary(&query_calculate_reward(deps, env, token_id)?)
         }
         QueryMsg::State {} => to_binary(&query_state(deps)?),
+        QueryMsg::PendingOwner {} => to_binary(&query_pending_owner(deps.storage)?),
+        QueryMsg::RewardSchedule { kind } => to_binary(&query_reward_schedule(deps, kind)?),
+        QueryMsg::RewardSchedules {} => to_binary(&query_reward_schedules(deps)?),
+        QueryMsg::LevelConfig {} => {
+            to_binary(&LEVEL_CONFIG.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::DragonLevel { token_id } => {
+            to_binary(&query_dragon_level(deps, env, token_id)?)
+        }
+        QueryMsg::Emissions {} => to_binary(&query_emissions(deps, env)?),
+        QueryMsg::PauseStatus {} => to_binary(&query_pause_status(deps, env)?),
+        QueryMsg::Owed { address } => to_binary(&query_owed(deps, address)?),
+        QueryMsg::Portfolio {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_portfolio(deps, env, owner, start_after, limit)?),
+        QueryMsg::BreedingFee {} => to_binary(&BreedingFeeResponse {
+            fee: BREEDING_FEE.may_load(deps.storage)?,
+        }),
+        QueryMsg::BreedingConsent { token_id } => {
+            to_binary(&BREEDING_CONSENTS.may_load(deps.storage, token_id.u64())?)
+        }
+        QueryMsg::AuthorizedMinters {} => to_binary(&query_authorized_minters(deps)?),
+        QueryMsg::VotingPowerAtHeight { address, height } => {
+            let height = height.unwrap_or(env.block.height);
+            to_binary(&VotingPowerAtHeightResponse {
+                power: snapshot::voting_power_at(deps.storage, &address, height)?,
+                height,
+            })
+        }
+        QueryMsg::TotalPowerAtHeight { height } => {
+            let height = height.unwrap_or(env.block.height);
+            to_binary(&TotalPowerAtHeightResponse {
+                power: snapshot::total_power_at(deps.storage, height)?,
+                height,
+            })
+        }
+        QueryMsg::VotingWeights {} => to_binary(&query_voting_weights(deps)?),
+        QueryMsg::StakedAt { owner, height } => to_binary(&query_staked_at(deps, owner, height)?),
+        QueryMsg::TotalStakedAt { height } => to_binary(&TotalStakedAtResponse {
+            height,
+            total: TOTAL_STAKED
+                .may_load_at_height(deps.storage, height)?
+                .unwrap_or_default(),
+        }),
+        QueryMsg::ClaimHistory {
+            token_id,
+            start_after,
+            limit,
+        } => to_binary(&query_claim_history(deps, token_id, start_after, limit)?),
+        QueryMsg::OwnerClaimHistory {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_owner_claim_history(deps, owner, start_after, limit)?),
         _ => Cw721Contract::default().query(deps, env, msg.into()),
     }
 }
 
-fn query_calculate_reward(deps: Deps, env: Env, token_id: Uint64) -> StdResult<Uint64> {
-    let dragon = DRAGON_INFO.load(deps.storage, token_id.u64()).unwrap();
-    let now = Uint64::new(env.block.time.seconds());
-    let mut reward = Uint64::zero();
-    if dragon.unstaking_process {
-        if dragon.kind == "common".to_string() {
-            let second_difference = dragon.reward_end_time.checked_sub(dragon.reward_start_time)?;
-            let second_difference_multiplied = second_difference.checked_mul(Uint64::new(500000))?;
-            reward = second_difference_multiplied.checked_div(Uint64::new(86400))?;
-        } else {
-            let second_difference = dragon.reward_end_time.checked_sub(dragon.reward_start_time)?;
-            let second_difference_multiplied = second_difference.checked_mul(Uint64::new(1000000))?;
-            let second_difference_multiplied_daily_income = second_difference_multiplied.checked_mul(Uint64::new(dragon.daily_income.parse::<u64>().unwrap()))?;
-            reward = second_difference_multiplied_daily_income.checked_div(Uint64::new(86400))?;
+fn query_calculate_reward(deps: Deps, env: Env, token_id: Uint64) -> StdResult<RewardBreakdown> {
+    let dragon = dragon_info().load(deps.storage, token_id.u64())?;
+    calculate_reward(deps.storage, &dragon, env.block.time.seconds())
+}
+
+fn query_dragon_level(deps: Deps, env: Env, token_id: Uint64) -> StdResult<DragonLevelResponse> {
+    let dragon = dragon_info().load(deps.storage, token_id.u64())?;
+    dragon_level(deps.storage, &dragon, env.block.time.seconds())
+}
+
+fn query_emissions(deps: Deps, env: Env) -> StdResult<EmissionsResponse> {
+    let weights: StdResult<Vec<_>> = EMISSION_WEIGHTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(kind, weight)| KindEmissionWeight { kind, weight }))
+        .collect();
+    Ok(EmissionsResponse {
+        config: EMISSION_CONFIG.may_load(deps.storage)?.unwrap_or_default(),
+        state: accrued_state(deps.storage, env.block.time.seconds())?,
+        weights: weights?,
+    })
+}
+
+fn query_portfolio(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PortfolioResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let now = env.block.time.seconds();
+    let min_stake_time = MIN_STAKE_TIME.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let dragons: Vec<(u64, Dragon)> = dragon_info()
+        .idx
+        .owner
+        .prefix(owner.clone())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    let next_start_after = match dragons.last() {
+        Some((token_id, _)) if dragons.len() == limit => Some(*token_id),
+        _ => None,
+    };
+
+    let mut kinds: BTreeMap<String, KindCount> = BTreeMap::new();
+    let mut pending_reward = Uint128::zero();
+    let mut reward_unavailable = vec![];
+    let mut unstaking = vec![];
+    let mut ready_to_plant = vec![];
+    for (_, dragon) in dragons {
+        let count = kinds.entry(dragon.kind.clone()).or_insert_with(|| KindCount {
+            kind: dragon.kind.clone(),
+            staked: 0,
+            unstaked: 0,
+        });
+        if !dragon.is_staked {
+            count.unstaked += 1;
+            continue;
         }
-    } else {
-        if dragon.kind == "common".to_string() {
-            let second_difference = now.checked_sub(dragon.reward_start_time)?;
-            let second_difference_multiplied = second_difference.checked_mul(Uint64::new(500000))?;
-            reward = second_difference_multiplied.checked_div(Uint64::new(86400))?;
-        } else {
-            let second_difference = now.checked_sub(dragon.reward_start_time)?;
-            let second_difference_multiplied = second_difference.checked_mul(Uint64::new(1000000))?;
-            let second_difference_multiplied_daily_income = second_difference_multiplied.checked_mul(Uint64::new(dragon.daily_income.parse::<u64>().unwrap()))?;
-            reward = second_difference_multiplied_daily_income.checked_div(Uint64::new(86400))?;
+        count.staked += 1;
+        match calculate_reward(deps.storage, &dragon, now) {
+            Ok(reward) => pending_reward = pending_reward.checked_add(reward.amount)?,
+            Err(_) => reward_unavailable.push(dragon.token_id.clone()),
+        }
+        if dragon.unstaking_process {
+            unstaking.push(UnstakingDragon {
+                token_id: dragon.token_id,
+                withdrawable_at: dragon.unstaking_start_time.checked_add(min_stake_time)?,
+            });
+        } else if dragon.is_fertile(now) {
+            ready_to_plant.push(dragon.token_id);
+        }
+    }
+
+    Ok(PortfolioResponse {
+        page_kinds: kinds.into_values().collect(),
+        page_pending_reward: pending_reward,
+        reward_unavailable,
+        owed: OWED.may_load(deps.storage, &owner_addr)?.unwrap_or_default(),
+        unstaking,
+        ready_to_plant,
+        next_start_after,
+        owner,
+    })
+}
+
+fn query_owed(deps: Deps, address: String) -> StdResult<OwedResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    Ok(OwedResponse {
+        amount: OWED.may_load(deps.storage, &addr)?.unwrap_or_default(),
+        address,
+    })
+}
+
+fn query_pause_status(deps: Deps, env: Env) -> StdResult<PauseStatusResponse> {
+    let groups = [
+        PauseGroup::Mint,
+        PauseGroup::Stake,
+        PauseGroup::Claim,
+        PauseGroup::Plant,
+        PauseGroup::Transfer,
+    ];
+    let mut paused = vec![];
+    for group in groups {
+        if let Some(pause) = active_pause(deps.storage, &env.block, group)? {
+            paused.push(GroupPause {
+                group,
+                reason: pause.reason,
+                expires: pause.expires,
+            });
         }
     }
-    Ok(reward)
+    Ok(PauseStatusResponse {
+        guardian: GUARDIAN.may_load(deps.storage)?.map(|guardian| guardian.to_string()),
+        paused,
+    })
+}
+
+fn query_reward_schedule(deps: Deps, kind: String) -> StdResult<RewardSchedule> {
+    REWARD_SCHEDULES.load(deps.storage, &kind)
+}
+
+fn query_reward_schedules(deps: Deps) -> StdResult<RewardSchedulesResponse> {
+    let schedules: StdResult<Vec<_>> = REWARD_SCHEDULES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|(kind, schedule)| KindRewardSchedule { kind, schedule })
+        })
+        .collect();
+    Ok(RewardSchedulesResponse {
+        schedules: schedules?,
+    })
 }
 
 fn query_dragon(deps: Deps, id: Uint64) -> StdResult<DragonResponse> {
-    let dragon = DRAGON_INFO.load(deps.storage, id.u64())?;
+    let dragon = dragon_info().load(deps.storage, id.u64())?;
     Ok(DragonResponse {
         token_id: dragon.token_id,
         owner: dragon.owner,
@@ -639,6 +1664,7 @@ fn query_dragon(deps: Deps, id: Uint64) -> StdResult<DragonResponse> {
         unstaking_start_time: dragon.unstaking_start_time,
         unstaking_process: dragon.unstaking_process,
         reward_end_time: dragon.reward_end_time,
+        xp: dragon.xp,
     })
 }
 
@@ -658,11 +1684,98 @@ fn query_config(deps: Deps) -> StdResult<CollectionInfoResponse> {
 fn query_state(deps: Deps) -> StdResult<StateResponse> {
     let state = STATE.load(deps.storage)?;
     Ok(StateResponse {
-        owner: state.owner,
-        reward_contract_address: state.reward_contract_address
+        owner: state.owner.to_string(),
+        reward_contract_address: state.reward_contract_address,
+        treasury: state.treasury.map(String::from),
+        emergency_unstake_penalty: state.emergency_unstake_penalty,
+    })
+}
+
+fn query_claim_history(
+    deps: Deps,
+    token_id: Uint64,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ClaimHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let claims = claim_history()
+        .idx
+        .token_id
+        .prefix(token_id.u64())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, claim)| claim))
+        .collect::<StdResult<_>>()?;
+    Ok(ClaimHistoryResponse {
+        total_claimed: DRAGON_CLAIM_TOTALS
+            .may_load(deps.storage, token_id.u64())?
+            .unwrap_or_default(),
+        claims,
+    })
+}
+
+fn query_owner_claim_history(
+    deps: Deps,
+    owner: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ClaimHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let total_claimed = OWNER_CLAIM_TOTALS
+        .may_load(deps.storage, &owner)?
+        .unwrap_or_default();
+    let claims = claim_history()
+        .idx
+        .owner
+        .prefix(owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, claim)| claim))
+        .collect::<StdResult<_>>()?;
+    Ok(ClaimHistoryResponse {
+        total_claimed,
+        claims,
+    })
+}
+
+fn query_voting_weights(deps: Deps) -> StdResult<VotingWeightsResponse> {
+    let weights = REWARD_SCHEDULES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|kind| {
+            let kind = kind?;
+            Ok(KindVotingWeight {
+                weight: VOTING_WEIGHTS
+                    .may_load(deps.storage, &kind)?
+                    .unwrap_or_else(|| Uint128::new(1)),
+                kind,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(VotingWeightsResponse { weights })
+}
+
+fn query_staked_at(deps: Deps, owner: String, height: u64) -> StdResult<StakedAtResponse> {
+    let token_ids = snapshot::staked_at(deps.storage, &owner, height)?
+        .into_iter()
+        .map(Uint64::new)
+        .collect();
+    Ok(StakedAtResponse {
+        owner,
+        height,
+        token_ids,
     })
 }
 
+fn query_authorized_minters(deps: Deps) -> StdResult<AuthorizedMintersResponse> {
+    let minters = MINTERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|minter| minter.map(String::from))
+        .collect::<StdResult<_>>()?;
+    Ok(AuthorizedMintersResponse { minters })
+}
+
 // settings for pagination
 const MAX_LIMIT: u32 = 30;
 const DEFAULT_LIMIT: u32 = 10;
@@ -674,7 +1787,7 @@ fn range_dragons(
 ) -> StdResult<DragonListResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start = start_after.map(Bound::exclusive);
-    let dragons: StdResult<Vec<_>> = DRAGON_INFO
+    let dragons: StdResult<Vec<_>> = dragon_info()
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
         .collect();
@@ -689,12 +1802,74 @@ fn range_user_dragons(
     start_after: Option<u64>,
     limit: Option<u32>,
     owner: String,
+    is_staked: Option<bool>,
+) -> StdResult<DragonListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let dragons: StdResult<Vec<_>> = dragon_info()
+        .idx
+        .owner
+        .prefix(owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match (item, is_staked) {
+            (Ok((_, dragon)), Some(is_staked)) => dragon.is_staked == is_staked,
+            _ => true,
+        })
+        .take(limit)
+        .collect();
+    let res = DragonListResponse {
+        dragons: dragons?.into_iter().map(|l| l.1.into()).collect(),
+    };
+    Ok(res)
+}
+
+fn query_user_dragons(deps: Deps, owner: String) -> StdResult<DragonListResponse> {
+    let dragons: StdResult<Vec<_>> = dragon_info()
+        .idx
+        .owner
+        .prefix(owner)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect();
+    let res = DragonListResponse {
+        dragons: dragons?.into_iter().map(|l| l.1.into()).collect(),
+    };
+    Ok(res)
+}
+
+fn range_kind_dragons(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    kind: String,
+) -> StdResult<DragonListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let dragons: StdResult<Vec<_>> = dragon_info()
+        .idx
+        .kind
+        .prefix(kind)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect();
+    let res = DragonListResponse {
+        dragons: dragons?.into_iter().map(|l| l.1.into()).collect(),
+    };
+    Ok(res)
+}
+
+fn range_staked_dragons(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    is_staked: bool,
 ) -> StdResult<DragonListResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start = start_after.map(Bound::exclusive);
-    let dragons: StdResult<Vec<_>> = DRAGON_INFO
+    let dragons: StdResult<Vec<_>> = dragon_info()
+        .idx
+        .is_staked
+        .prefix(is_staked as u8)
         .range(deps.storage, start, None, Order::Ascending)
-        .filter(|r| r.as_ref().unwrap().1.owner == owner)
         .take(limit)
         .collect();
     let res = DragonListResponse {
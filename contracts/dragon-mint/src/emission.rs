@@ -0,0 +1,128 @@
+use crate::state::{
+    dragon_info, DragonEmission, EmissionState, DRAGON_EMISSIONS, EMISSION_CONFIG, EMISSION_STATE,
+    EMISSION_WEIGHTS,
+};
+use cosmwasm_std::{Decimal, Order, StdResult, Storage, Uint128, Uint64};
+
+/// Emission weight of a kind. Kinds without a configured weight count as 1.
+pub fn kind_weight(storage: &dyn Storage, kind: &str) -> StdResult<Uint128> {
+    Ok(EMISSION_WEIGHTS
+        .may_load(storage, kind)?
+        .unwrap_or_else(|| Uint128::new(1)))
+}
+
+/// Returns the accumulator advanced to `now` without saving it.
+pub fn accrued_state(storage: &dyn Storage, now: u64) -> StdResult<EmissionState> {
+    let config = EMISSION_CONFIG.may_load(storage)?.unwrap_or_default();
+    let mut state = EMISSION_STATE.may_load(storage)?.unwrap_or_default();
+    let elapsed = now.saturating_sub(state.last_update.u64());
+    // nothing is emitted while no dragon is staked, so the budget is never exceeded
+    if config.enabled && elapsed > 0 && !state.total_weight.is_zero() {
+        let emitted = config
+            .tokens_per_second
+            .checked_mul(Uint128::from(elapsed))?;
+        state.reward_per_weight += Decimal::from_ratio(emitted, state.total_weight);
+    }
+    state.last_update = Uint64::new(now.max(state.last_update.u64()));
+    Ok(state)
+}
+
+pub fn update_emissions(storage: &mut dyn Storage, now: u64) -> StdResult<EmissionState> {
+    let state = accrued_state(storage, now)?;
+    EMISSION_STATE.save(storage, &state)?;
+    Ok(state)
+}
+
+/// Reward earned by a dragon with the given accumulator, including what is already pending.
+pub fn earned(state: &EmissionState, emission: &DragonEmission) -> StdResult<Uint128> {
+    let unpaid = emission.weight * (state.reward_per_weight - emission.reward_per_weight_paid);
+    Ok(emission.pending.checked_add(unpaid)?)
+}
+
+fn settle(state: &EmissionState, emission: &mut DragonEmission) -> StdResult<()> {
+    emission.pending = earned(state, emission)?;
+    emission.reward_per_weight_paid = state.reward_per_weight;
+    Ok(())
+}
+
+/// Adds a staked dragon to the emission accounting. Emissions it earned before leaving,
+/// e.g. ahead of a cancelled unstake, stay pending.
+pub fn join(storage: &mut dyn Storage, token_id: u64, kind: &str, now: u64) -> StdResult<()> {
+    let mut state = update_emissions(storage, now)?;
+    let weight = kind_weight(storage, kind)?;
+    state.total_weight = state.total_weight.checked_add(weight)?;
+    EMISSION_STATE.save(storage, &state)?;
+    let pending = DRAGON_EMISSIONS
+        .may_load(storage, token_id)?
+        .map(|emission| emission.pending)
+        .unwrap_or_default();
+    let emission = DragonEmission {
+        weight,
+        reward_per_weight_paid: state.reward_per_weight,
+        pending,
+    };
+    DRAGON_EMISSIONS.save(storage, token_id, &emission)
+}
+
+/// Sets the weight of a kind. Dragons of the kind that are earning emissions are settled at
+/// their old weight first and earn at the new one from `now` on.
+pub fn reweight(storage: &mut dyn Storage, kind: &str, weight: Uint128, now: u64) -> StdResult<()> {
+    let mut state = update_emissions(storage, now)?;
+    let token_ids: Vec<u64> = dragon_info()
+        .idx
+        .kind
+        .prefix(kind.to_string())
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    for token_id in token_ids {
+        let mut emission = match DRAGON_EMISSIONS.may_load(storage, token_id)? {
+            // dragons that left keep a zero weight until they join again
+            Some(emission) if !emission.weight.is_zero() => emission,
+            _ => continue,
+        };
+        settle(&state, &mut emission)?;
+        state.total_weight = state
+            .total_weight
+            .checked_sub(emission.weight)?
+            .checked_add(weight)?;
+        emission.weight = weight;
+        DRAGON_EMISSIONS.save(storage, token_id, &emission)?;
+    }
+    EMISSION_STATE.save(storage, &state)?;
+    EMISSION_WEIGHTS.save(storage, kind, &weight)
+}
+
+/// Stops a dragon from earning emissions, keeping what it earned so far claimable.
+pub fn leave(storage: &mut dyn Storage, token_id: u64, now: u64) -> StdResult<()> {
+    let mut emission = match DRAGON_EMISSIONS.may_load(storage, token_id)? {
+        Some(emission) => emission,
+        None => return Ok(()),
+    };
+    let mut state = update_emissions(storage, now)?;
+    settle(&state, &mut emission)?;
+    state.total_weight = state.total_weight.checked_sub(emission.weight)?;
+    emission.weight = Uint128::zero();
+    EMISSION_STATE.save(storage, &state)?;
+    DRAGON_EMISSIONS.save(storage, token_id, &emission)
+}
+
+/// Removes an unstaked dragon from the emission accounting.
+pub fn remove(storage: &mut dyn Storage, token_id: u64, now: u64) -> StdResult<()> {
+    leave(storage, token_id, now)?;
+    DRAGON_EMISSIONS.remove(storage, token_id);
+    Ok(())
+}
+
+/// Settles the emissions of a dragon and returns the amount it was paid.
+pub fn claim(storage: &mut dyn Storage, token_id: u64, now: u64) -> StdResult<Uint128> {
+    let mut emission = match DRAGON_EMISSIONS.may_load(storage, token_id)? {
+        Some(emission) => emission,
+        None => return Ok(Uint128::zero()),
+    };
+    let state = update_emissions(storage, now)?;
+    settle(&state, &mut emission)?;
+    let amount = emission.pending;
+    emission.pending = Uint128::zero();
+    DRAGON_EMISSIONS.save(storage, token_id, &emission)?;
+    Ok(amount)
+}
@@ -1,6 +1,8 @@
 use cosmwasm_std::{OverflowError, StdError};
 use cw721_base::ContractError as Cw721ContractError;
 use cw_utils::PaymentError;
+use stake_dragons_utils::migrate::MigrateError;
+use stake_dragons_utils::ownership::OwnershipError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -14,6 +16,9 @@ pub enum ContractError {
     #[error("721 error : {method}")]
     NftContractError { method: String },
 
+    #[error("{0}")]
+    Cw721(#[from] Cw721ContractError),
+
     #[error("Invalid size")]
     InvalidSize {},
 
@@ -32,9 +37,21 @@ pub enum ContractError {
     #[error("Unexpected trait type: {trait_type}")]
     UnexpectedTraitType { trait_type: String },
 
+    #[error("Missing trait: {trait_type}")]
+    MissingTrait { trait_type: String },
+
+    #[error("Trait given more than once: {trait_type}")]
+    DuplicateTrait { trait_type: String },
+
+    #[error("Invalid value for trait {trait_type}: {value}")]
+    InvalidTraitValue { trait_type: String, value: String },
+
     #[error("Staked dragon cannot be transferred")]
     StakedDragonCantBeTransferred {},
 
+    #[error("Staked dragon cannot be burned")]
+    StakedDragonCantBeBurned {},
+
     #[error("Dragon must be staked to be hatch")]
     DragonNotStaked {},
 
@@ -44,9 +61,30 @@ pub enum ContractError {
     #[error("Kind not found")]
     KindNotFound {},
 
+    #[error("Dragons of kind {kind} still exist")]
+    KindInUse { kind: String },
+
+    #[error("No reward schedule for kind {kind}")]
+    MissingRewardSchedule { kind: String },
+
+    #[error("Invalid reward schedule: {reason}")]
+    InvalidRewardSchedule { reason: String },
+
+    #[error("Invalid level config: {reason}")]
+    InvalidLevelConfig { reason: String },
+
     #[error("Ovulation period has not ended.")]
     OvulationInProgress {},
 
+    #[error("A dragon cannot breed with itself")]
+    SameParents {},
+
+    #[error("Breeding consent not found for dragon {token_id}")]
+    BreedingConsentNotFound { token_id: String },
+
+    #[error("Breeding fee must be {fee}")]
+    InvalidBreedingFee { fee: String },
+
     #[error("{0}")]
     Payment(#[from] PaymentError),
 
@@ -64,6 +102,27 @@ pub enum ContractError {
 
     #[error("{0}")]
     Overflow(#[from] OverflowError),
+
+    #[error("{group} is paused: {reason}")]
+    Paused { group: String, reason: String },
+
+    #[error("{0}")]
+    Ownership(#[from] OwnershipError),
+
+    #[error("Nothing is owed to {address}")]
+    NothingOwed { address: String },
+
+    #[error("Unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("Emergency unstake is disabled")]
+    EmergencyUnstakeDisabled {},
+
+    #[error("Emergency unstake penalty cannot exceed 100%")]
+    InvalidEmergencyUnstakePenalty {},
+
+    #[error("{0}")]
+    Migrate(#[from] MigrateError),
 }
 
 impl From<ContractError> for Cw721ContractError {
@@ -71,6 +130,7 @@ impl From<ContractError> for Cw721ContractError {
         match err {
             ContractError::Unauthorized {} => Cw721ContractError::Unauthorized {},
             ContractError::Claimed {} => Cw721ContractError::Claimed {},
+            ContractError::Cw721(err) => err,
             _ => unreachable!("cannot connect {:?} to cw721ContractError", err),
         }
     }
@@ -1,14 +1,41 @@
-use crate::msg::{DragonBirthMsg, DragonBirthWrapper};
+use crate::msg::{DragonBirthMsg, DragonBirthWrapper, DragonParent};
+use crate::state::Dragon;
 use crate::ContractError;
 
 pub fn generate_dragon_birth_msg(
-    id: String,
+    seq: u64,
+    dragon: &Dragon,
     owner: String,
 ) -> Result<DragonBirthWrapper, ContractError> {
     let msg = DragonBirthWrapper {
         dragon_birth: DragonBirthMsg {
-            id: "0000".to_string() + id.as_str(),
+            id: "plant".to_string() + seq.to_string().as_str(),
             owner,
+            parents: vec![DragonParent {
+                token_id: dragon.token_id.clone(),
+                kind: dragon.kind.clone(),
+            }],
+        },
+    };
+    Ok(msg)
+}
+
+pub fn generate_breeding_birth_msg(
+    seq: u64,
+    owner: String,
+    parents: [&Dragon; 2],
+) -> Result<DragonBirthWrapper, ContractError> {
+    let msg = DragonBirthWrapper {
+        dragon_birth: DragonBirthMsg {
+            id: "breed".to_string() + seq.to_string().as_str(),
+            owner,
+            parents: parents
+                .iter()
+                .map(|dragon| DragonParent {
+                    token_id: dragon.token_id.clone(),
+                    kind: dragon.kind.clone(),
+                })
+                .collect(),
         },
     };
     Ok(msg)
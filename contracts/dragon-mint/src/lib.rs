@@ -1,8 +1,14 @@
 pub mod contract;
+pub mod emission;
 mod error;
 mod helper;
 pub mod msg;
+pub mod pause;
+pub mod reward;
+pub mod snapshot;
 pub mod state;
-pub mod tests;
+pub mod traits;
+#[cfg(test)]
+mod tests;
 
 pub use crate::error::ContractError;
@@ -1,4 +1,7 @@
-use cosmwasm_std::{Binary, Uint128, Uint64};
+use crate::state::{
+    ClaimRecord, EmissionConfig, EmissionState, LevelConfig, PauseGroup, RewardSchedule,
+};
+use cosmwasm_std::{Binary, Coin, Decimal, Uint128, Uint64};
 use cw721_base::msg::InstantiateMsg as Cw721InstantiateMsg;
 use cw721_base::msg::MintMsg as Cw721MintMsg;
 use cw721_base::msg::QueryMsg as Cw721QueryMsg;
@@ -12,6 +15,22 @@ pub struct InstantiateMsg {
     pub size: Uint64,
     pub base_price: Uint64,
     pub reward_contract_address: String,
+    pub reward_schedules: Vec<KindRewardSchedule>,
+    pub breeding_fee: Option<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct MigrateMsg {
+    /// Reward schedules set before migrating. v0.1 paid fixed rates and stored none, so
+    /// migrating from it needs one for every kind its dragons have.
+    #[serde(default)]
+    pub reward_schedules: Vec<KindRewardSchedule>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KindRewardSchedule {
+    pub kind: String,
+    pub schedule: RewardSchedule,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
@@ -59,12 +78,65 @@ pub enum ExecuteMsg {
     UpdateMinStakeTime {
         time: Uint64,
     },
-    UpdateOwner {
+    /// Starts a two-step ownership handover, completed by `AcceptOwnership` from `new_owner`.
+    ProposeOwner {
         new_owner: String,
+        expires: Option<Expiration>,
     },
+    AcceptOwnership {},
+    CancelOwnershipProposal {},
     UpdateRewardContractAddress {
         new_address: String,
     },
+    UpdateTreasury {
+        treasury: String,
+    },
+    /// Share of pending rewards sent to the treasury on `EmergencyUnstake`, `None` disables it.
+    UpdateEmergencyUnstakePenalty {
+        penalty: Option<Decimal>,
+    },
+    SetRewardSchedule {
+        kind: String,
+        schedule: RewardSchedule,
+    },
+    RemoveRewardSchedule {
+        kind: String,
+    },
+    UpdateLevelConfig {
+        config: LevelConfig,
+    },
+    UpdateEmissionConfig {
+        config: EmissionConfig,
+    },
+    SetEmissionWeight {
+        kind: String,
+        weight: Uint128,
+    },
+    /// Voting power of each staked dragon of `kind`, 1 until set.
+    SetVotingWeight {
+        kind: String,
+        weight: Uint128,
+    },
+    UpdateGuardian {
+        guardian: Option<String>,
+    },
+    /// Lets `address` mint dragons alongside the cw721 minter, e.g. egg-mint or the whitelist contract.
+    AddMinter {
+        address: String,
+    },
+    RemoveMinter {
+        address: String,
+    },
+    /// Owner or guardian only.
+    Pause {
+        groups: Vec<PauseGroup>,
+        reason: String,
+        expires: Option<Expiration>,
+    },
+    /// Owner or guardian only.
+    Unpause {
+        groups: Vec<PauseGroup>,
+    },
     TransferNft {
         recipient: String,
         token_id: Uint64,
@@ -97,18 +169,48 @@ pub enum ExecuteMsg {
     PlantEgg {
         token_id: Uint64,
     },
+    /// Breeds two fertile dragons into an egg owned by the owner of `token_id`.
+    /// If `partner_id` belongs to someone else, its owner must have approved the pairing first.
+    BreedDragons {
+        token_id: Uint64,
+        partner_id: Uint64,
+    },
+    ApproveBreeding {
+        token_id: Uint64,
+        partner_id: Uint64,
+    },
+    RevokeBreeding {
+        token_id: Uint64,
+    },
+    UpdateBreedingFee {
+        fee: Option<Coin>,
+    },
     StakeDragon {
         token_id: Uint64,
     },
     StartUnstakingProcess {
         token_id: Uint64,
     },
+    /// Stops an unstaking process and returns the dragon to full staking.
+    CancelUnstaking {
+        token_id: Uint64,
+    },
     UnstakeDragon {
         token_id: Uint64,
     },
+    /// Unstakes right away, skipping the unstaking period for a share of the pending rewards.
+    EmergencyUnstake {
+        token_id: Uint64,
+    },
     ClaimReward {
         token_id: Uint64,
     },
+    ClaimAllRewards {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Retries the payout of rewards the reward contract failed to pay.
+    WithdrawOwed {},
     Claim {
         token_id: Uint64,
     },
@@ -155,11 +257,75 @@ pub enum QueryMsg {
         start_after: Option<u64>,
         limit: Option<u32>,
         owner: String,
+        is_staked: Option<bool>,
+    },
+    RangeKindDragons {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        kind: String,
+    },
+    RangeStakedDragons {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        is_staked: bool,
     },
     CalculateReward {
         token_id: Uint64,
     },
     State {},
+    PendingOwner {},
+    RewardSchedule {
+        kind: String,
+    },
+    RewardSchedules {},
+    LevelConfig {},
+    Emissions {},
+    PauseStatus {},
+    Owed {
+        address: String,
+    },
+    /// Summary of `owner`'s dragons, a page of them at a time.
+    Portfolio {
+        owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    DragonLevel {
+        token_id: Uint64,
+    },
+    BreedingFee {},
+    BreedingConsent {
+        token_id: Uint64,
+    },
+    AuthorizedMinters {},
+    ClaimHistory {
+        token_id: Uint64,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    OwnerClaimHistory {
+        owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Dragons `owner` had staked at the start of block `height`.
+    StakedAt {
+        owner: String,
+        height: u64,
+    },
+    /// Number of dragons staked at the start of block `height`.
+    TotalStakedAt {
+        height: u64,
+    },
+    /// Voting power of `address` from its staked dragons, at the current height by default.
+    VotingPowerAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+    TotalPowerAtHeight {
+        height: Option<u64>,
+    },
+    VotingWeights {},
 }
 
 impl From<QueryMsg> for Cw721QueryMsg {
@@ -214,15 +380,151 @@ pub struct CollectionInfoResponse {
 pub struct StateResponse {
     pub owner: String,
     pub reward_contract_address: String,
+    pub treasury: Option<String>,
+    pub emergency_unstake_penalty: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardSchedulesResponse {
+    pub schedules: Vec<KindRewardSchedule>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KindEmissionWeight {
+    pub kind: String,
+    pub weight: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmissionsResponse {
+    pub config: EmissionConfig,
+    pub state: EmissionState,
+    pub weights: Vec<KindEmissionWeight>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GroupPause {
+    pub group: PauseGroup,
+    pub reason: String,
+    pub expires: Option<Expiration>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseStatusResponse {
+    pub guardian: Option<String>,
+    pub paused: Vec<GroupPause>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KindCount {
+    pub kind: String,
+    pub staked: u32,
+    pub unstaked: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnstakingDragon {
+    pub token_id: String,
+    /// Time from which `UnstakeDragon` succeeds.
+    pub withdrawable_at: Uint64,
+}
+
+/// Everything a wallet page needs about an owner's dragons. All fields but `owed` cover the
+/// dragons of this page only; clients add up the pages for the whole portfolio.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PortfolioResponse {
+    pub owner: String,
+    /// Staked and unstaked counts by kind of the dragons on this page.
+    pub page_kinds: Vec<KindCount>,
+    /// Reward pending on the staked dragons of this page.
+    pub page_pending_reward: Uint128,
+    /// Staked dragons left out of `page_pending_reward` because their reward cannot be computed,
+    /// e.g. when their kind has no reward schedule.
+    pub reward_unavailable: Vec<String>,
+    pub owed: Uint128,
+    pub unstaking: Vec<UnstakingDragon>,
+    /// Dragons whose ovulation period has ended, ready for `PlantEgg`.
+    pub ready_to_plant: Vec<String>,
+    /// `start_after` of the next page, unset on the last one.
+    pub next_start_after: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwedResponse {
+    pub address: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BreedingFeeResponse {
+    pub fee: Option<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DragonParent {
+    pub token_id: String,
+    pub kind: String,
+}
+
+/// `parents` holds the planting dragon for planted eggs. Bred eggs carry both parents so
+/// the egg contract can weight the rarity of the hatched dragon by their kinds.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct DragonBirthMsg {
     pub id: String,
     pub owner: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parents: Vec<DragonParent>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct DragonBirthWrapper {
     pub dragon_birth: DragonBirthMsg,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuthorizedMintersResponse {
+    pub minters: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimHistoryResponse {
+    /// Everything ever paid out, not only the claims in this page.
+    pub total_claimed: Uint128,
+    pub claims: Vec<ClaimRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakedAtResponse {
+    pub owner: String,
+    pub height: u64,
+    pub token_ids: Vec<Uint64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalStakedAtResponse {
+    pub height: u64,
+    pub total: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VotingPowerAtHeightResponse {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalPowerAtHeightResponse {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KindVotingWeight {
+    pub kind: String,
+    pub weight: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VotingWeightsResponse {
+    pub weights: Vec<KindVotingWeight>,
+}
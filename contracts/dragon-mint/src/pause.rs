@@ -0,0 +1,58 @@
+use crate::msg::ExecuteMsg;
+use crate::state::{PauseGroup, PauseInfo, PAUSES};
+use crate::ContractError;
+use cosmwasm_std::{BlockInfo, StdResult, Storage};
+
+/// Operation group an execute message belongs to, if it can be paused.
+/// Owner and guardian messages are never paused so a pause can always be lifted.
+pub fn pause_group(msg: &ExecuteMsg) -> Option<PauseGroup> {
+    match msg {
+        ExecuteMsg::Mint(_) => Some(PauseGroup::Mint),
+        ExecuteMsg::StakeDragon { .. }
+        | ExecuteMsg::StartUnstakingProcess { .. }
+        | ExecuteMsg::CancelUnstaking { .. }
+        | ExecuteMsg::UnstakeDragon { .. }
+        | ExecuteMsg::EmergencyUnstake { .. } => Some(PauseGroup::Stake),
+        ExecuteMsg::ClaimReward { .. }
+        | ExecuteMsg::ClaimAllRewards { .. }
+        | ExecuteMsg::Claim { .. }
+        | ExecuteMsg::WithdrawOwed {} => Some(PauseGroup::Claim),
+        ExecuteMsg::PlantEgg { .. } | ExecuteMsg::BreedDragons { .. } => Some(PauseGroup::Plant),
+        ExecuteMsg::TransferNft { .. } | ExecuteMsg::SendNft { .. } | ExecuteMsg::Burn { .. } => {
+            Some(PauseGroup::Transfer)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the pause of `group` unless there is none or it has expired.
+pub fn active_pause(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    group: PauseGroup,
+) -> StdResult<Option<PauseInfo>> {
+    Ok(PAUSES
+        .may_load(storage, group.as_str())?
+        .filter(|pause| match pause.expires {
+            Some(expires) => !expires.is_expired(block),
+            None => true,
+        }))
+}
+
+pub fn assert_not_paused(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    msg: &ExecuteMsg,
+) -> Result<(), ContractError> {
+    let group = match pause_group(msg) {
+        Some(group) => group,
+        None => return Ok(()),
+    };
+    match active_pause(storage, block, group)? {
+        Some(pause) => Err(ContractError::Paused {
+            group: group.as_str().to_string(),
+            reason: pause.reason,
+        }),
+        None => Ok(()),
+    }
+}
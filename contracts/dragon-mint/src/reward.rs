@@ -0,0 +1,184 @@
+use crate::emission::{self, accrued_state, earned};
+use crate::state::{
+    claim_history, ClaimRecord, Dragon, LevelConfig, CLAIM_HISTORY_SEQ, DRAGON_CLAIM_TOTALS,
+    DRAGON_EMISSIONS, EMISSION_CONFIG, LEVEL_CONFIG, OWNER_CLAIM_TOTALS, REWARD_SCHEDULES,
+};
+use cosmwasm_std::{BlockInfo, Decimal, StdError, StdResult, Storage, Uint128, Uint64};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardBreakdown {
+    pub elapsed_seconds: Uint64,
+    pub rate: Uint128,
+    pub precision: u32,
+    /// Level reached at the end of the period
+    pub level: u32,
+    pub multiplier: Decimal,
+    /// Set in emission mode, where the amount is the dragon's share of the emission budget and
+    /// the schedule and level multiplier do not apply.
+    pub emission_weight: Option<Uint128>,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DragonLevelResponse {
+    pub xp: Uint64,
+    pub level: u32,
+    pub multiplier: Decimal,
+    pub next_level_xp: Option<Uint64>,
+    /// Only set while the dragon is staked and still earning xp.
+    pub seconds_to_next_level: Option<Uint64>,
+}
+
+/// Computes the reward a dragon has accrued up to `now`.
+/// Used by both `ClaimReward` and the `CalculateReward` query so the two always agree.
+/// Each part of the period is boosted by the level the dragon held during it.
+pub fn calculate_reward(
+    storage: &dyn Storage,
+    dragon: &Dragon,
+    now: u64,
+) -> StdResult<RewardBreakdown> {
+    let schedule = REWARD_SCHEDULES
+        .may_load(storage, &dragon.kind)?
+        .ok_or_else(|| StdError::not_found(format!("reward schedule for {}", dragon.kind)))?;
+
+    let (_, reward_end_time) = dragon.reward_period(now);
+    let elapsed_seconds = if dragon.is_staked && !dragon.reward_start_time.is_zero() {
+        reward_end_time.checked_sub(dragon.reward_start_time)?
+    } else {
+        Uint64::zero()
+    };
+
+    let level = dragon_level(storage, dragon, now)?;
+
+    if EMISSION_CONFIG.may_load(storage)?.unwrap_or_default().enabled {
+        let token_id = dragon.token_id.parse::<u64>().map_err(|_| {
+            StdError::parse_err("u64", format!("invalid token_id {}", dragon.token_id))
+        })?;
+        let (emission_weight, amount) = match DRAGON_EMISSIONS.may_load(storage, token_id)? {
+            Some(emission) => (
+                emission.weight,
+                earned(&accrued_state(storage, now)?, &emission)?,
+            ),
+            None => (Uint128::zero(), Uint128::zero()),
+        };
+        return Ok(RewardBreakdown {
+            elapsed_seconds,
+            rate: schedule.rate,
+            precision: schedule.precision,
+            level: level.level,
+            multiplier: Decimal::one(),
+            emission_weight: Some(emission_weight),
+            amount,
+        });
+    }
+
+    let segments = if elapsed_seconds.is_zero() {
+        vec![]
+    } else {
+        level_segments(
+            &LEVEL_CONFIG.may_load(storage)?.unwrap_or_default(),
+            dragon.current_xp(dragon.reward_start_time.u64())?,
+            elapsed_seconds.u64(),
+        )
+    };
+    Ok(RewardBreakdown {
+        elapsed_seconds,
+        rate: schedule.rate,
+        precision: schedule.precision,
+        level: level.level,
+        multiplier: level.multiplier,
+        emission_weight: None,
+        amount: schedule.boosted_reward_for(&segments)?,
+    })
+}
+
+/// Splits `seconds` of staking started with `xp` at each level reached on the way, as
+/// `(seconds, multiplier)` segments. One xp is earned per second.
+fn level_segments(config: &LevelConfig, mut xp: u64, mut seconds: u64) -> Vec<(u64, Decimal)> {
+    let mut segments = vec![];
+    while seconds > 0 {
+        let (_, multiplier) = config.level_for(xp);
+        let length = match config.next_threshold(xp) {
+            Some(threshold) => seconds.min(threshold.xp.u64() - xp),
+            None => seconds,
+        };
+        segments.push((length, multiplier));
+        xp += length;
+        seconds -= length;
+    }
+    segments
+}
+
+/// Returns the reward a claim pays out and starts a new reward period for the dragon.
+pub fn take_reward(
+    storage: &mut dyn Storage,
+    token_id: u64,
+    dragon: &mut Dragon,
+    now: u64,
+) -> StdResult<Uint128> {
+    let amount = calculate_reward(storage, dragon, now)?.amount;
+    // the emission accounting is settled in both modes so switching modes pays nothing twice
+    emission::claim(storage, token_id, now)?;
+    dragon.reset_reward_period(now);
+    Ok(amount)
+}
+
+/// Like `take_reward`, for a reward that is paid out, which is recorded in the claim history.
+pub fn pay_reward(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    token_id: u64,
+    dragon: &mut Dragon,
+) -> StdResult<Uint128> {
+    let now = block.time.seconds();
+    let (period_start, period_end) = dragon.reward_period(now);
+    let amount = take_reward(storage, token_id, dragon, now)?;
+    if amount.is_zero() {
+        return Ok(amount);
+    }
+    let id = CLAIM_HISTORY_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+    CLAIM_HISTORY_SEQ.save(storage, &id)?;
+    let record = ClaimRecord {
+        id,
+        token_id: Uint64::new(token_id),
+        owner: dragon.owner.clone(),
+        amount,
+        period_start,
+        period_end,
+        height: block.height,
+    };
+    claim_history().save(storage, id, &record)?;
+    let add = |total: Option<Uint128>| -> StdResult<_> {
+        Ok(total.unwrap_or_default().checked_add(amount)?)
+    };
+    DRAGON_CLAIM_TOTALS.update(storage, token_id, add)?;
+    OWNER_CLAIM_TOTALS.update(storage, &dragon.owner, add)?;
+    Ok(amount)
+}
+
+pub fn dragon_level(
+    storage: &dyn Storage,
+    dragon: &Dragon,
+    now: u64,
+) -> StdResult<DragonLevelResponse> {
+    let config = LEVEL_CONFIG.may_load(storage)?.unwrap_or_default();
+    let xp = dragon.current_xp(now)?;
+    let (level, multiplier) = config.level_for(xp);
+    let next = config.next_threshold(xp);
+    let seconds_to_next_level = match next {
+        Some(threshold) if dragon.is_staked && !dragon.unstaking_process => {
+            Some(Uint64::new(threshold.xp.u64() - xp))
+        }
+        _ => None,
+    };
+
+    Ok(DragonLevelResponse {
+        xp: Uint64::new(xp),
+        level,
+        multiplier,
+        next_level_xp: next.map(|threshold| threshold.xp),
+        seconds_to_next_level,
+    })
+}
@@ -0,0 +1,93 @@
+use crate::state::{KIND_STAKED, OWNER_KIND_STAKED, STAKED_DRAGONS, TOTAL_STAKED, VOTING_WEIGHTS};
+use cosmwasm_std::{Order, StdError, StdResult, Storage, Uint128};
+
+/// Records `token_id` as staked by `owner` from block `height` on.
+pub fn record_stake(
+    storage: &mut dyn Storage,
+    owner: &str,
+    kind: &str,
+    token_id: u64,
+    height: u64,
+) -> StdResult<()> {
+    STAKED_DRAGONS.save(storage, (owner, token_id), &true, height)?;
+    let increment = |count: Option<u64>| -> StdResult<_> { Ok(count.unwrap_or_default() + 1) };
+    TOTAL_STAKED.update(storage, height, increment)?;
+    OWNER_KIND_STAKED.update(storage, (owner, kind), height, increment)?;
+    KIND_STAKED.update(storage, kind, height, increment)?;
+    Ok(())
+}
+
+/// Records `token_id` as no longer staked by `owner` from block `height` on.
+pub fn record_unstake(
+    storage: &mut dyn Storage,
+    owner: &str,
+    kind: &str,
+    token_id: u64,
+    height: u64,
+) -> StdResult<()> {
+    STAKED_DRAGONS.save(storage, (owner, token_id), &false, height)?;
+    let decrement = |count: Option<u64>| {
+        count
+            .unwrap_or_default()
+            .checked_sub(1)
+            .ok_or_else(|| StdError::generic_err("staked count underflow"))
+    };
+    TOTAL_STAKED.update(storage, height, decrement)?;
+    OWNER_KIND_STAKED.update(storage, (owner, kind), height, decrement)?;
+    KIND_STAKED.update(storage, kind, height, decrement)?;
+    Ok(())
+}
+
+/// Dragons `owner` had staked at the start of block `height`.
+pub fn staked_at(storage: &dyn Storage, owner: &str, height: u64) -> StdResult<Vec<u64>> {
+    let token_ids: Vec<u64> = STAKED_DRAGONS
+        .prefix(owner)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    let mut staked = vec![];
+    for token_id in token_ids {
+        if STAKED_DRAGONS
+            .may_load_at_height(storage, (owner, token_id), height)?
+            .unwrap_or_default()
+        {
+            staked.push(token_id);
+        }
+    }
+    Ok(staked)
+}
+
+pub fn voting_weight_at(storage: &dyn Storage, kind: &str, height: u64) -> StdResult<Uint128> {
+    Ok(VOTING_WEIGHTS
+        .may_load_at_height(storage, kind, height)?
+        .unwrap_or_else(|| Uint128::new(1)))
+}
+
+/// Voting power of `owner` at the start of block `height`, from its staked dragons. Staked
+/// counts are never removed, so the kinds stored now cover every earlier height.
+pub fn voting_power_at(storage: &dyn Storage, owner: &str, height: u64) -> StdResult<Uint128> {
+    let kinds: Vec<String> = OWNER_KIND_STAKED
+        .prefix(owner)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    kinds.iter().try_fold(Uint128::zero(), |power, kind| {
+        let count = OWNER_KIND_STAKED
+            .may_load_at_height(storage, (owner, kind), height)?
+            .unwrap_or_default();
+        let weight = voting_weight_at(storage, kind, height)?;
+        Ok(power.checked_add(weight.checked_mul(count.into())?)?)
+    })
+}
+
+/// Voting power of all staked dragons at the start of block `height`.
+pub fn total_power_at(storage: &dyn Storage, height: u64) -> StdResult<Uint128> {
+    let kinds: Vec<String> = KIND_STAKED
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    kinds.iter().try_fold(Uint128::zero(), |power, kind| {
+        let count = KIND_STAKED
+            .may_load_at_height(storage, kind, height)?
+            .unwrap_or_default();
+        let weight = voting_weight_at(storage, kind, height)?;
+        Ok(power.checked_add(weight.checked_mul(count.into())?)?)
+    })
+}
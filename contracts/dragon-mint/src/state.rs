@@ -1,6 +1,13 @@
+use crate::msg::Claim;
 use crate::ContractError;
-use cosmwasm_std::{Response, Uint64};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{
+    Addr, Coin, Decimal, Empty, OverflowError, OverflowOperation, Response, StdError, StdResult,
+    Uint128, Uint64,
+};
+use cw_storage_plus::{
+    Index, IndexList, IndexedMap, Item, Map, MultiIndex, SnapshotItem, SnapshotMap, Strategy,
+};
+use cw_utils::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -16,8 +23,53 @@ pub struct CollectionInfo {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
-    pub owner: String,
+    pub owner: Addr,
     pub reward_contract_address: String,
+    /// Receives the rewards forfeited by emergency unstakes.
+    #[serde(default)]
+    pub treasury: Option<Addr>,
+    /// Share of pending rewards forfeited by an emergency unstake, disabled when unset.
+    #[serde(default)]
+    pub emergency_unstake_penalty: Option<Decimal>,
+}
+
+/// Daily reward paid to a staked dragon of a given kind.
+/// `rate` is expressed in reward token units scaled by `10^precision`, so a rate of 5 with a
+/// precision of 1 pays half a unit per day. `cap` limits the reward of a single accrual period.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardSchedule {
+    pub rate: Uint128,
+    pub precision: u32,
+    pub cap: Option<Uint128>,
+}
+
+pub const MAX_REWARD_PRECISION: u32 = 18;
+
+impl RewardSchedule {
+    pub fn reward_for(&self, seconds: u64) -> StdResult<Uint128> {
+        self.boosted_reward_for(&[(seconds, Decimal::one())])
+    }
+
+    /// Reward for consecutive `(seconds, multiplier)` segments of staking, each scaled by the
+    /// level multiplier held during it. The cap applies to the boosted total.
+    pub fn boosted_reward_for(&self, segments: &[(u64, Decimal)]) -> StdResult<Uint128> {
+        let mut boosted = Uint128::zero();
+        for &(seconds, multiplier) in segments {
+            let base = Uint128::from(seconds).checked_mul(self.rate)?;
+            let segment = base
+                .checked_multiply_ratio(multiplier.atomics(), Decimal::one().atomics())
+                .map_err(|_| {
+                    StdError::overflow(OverflowError::new(OverflowOperation::Mul, base, multiplier))
+                })?;
+            boosted = boosted.checked_add(segment)?;
+        }
+        let scale = Uint128::new(10).checked_pow(self.precision)?;
+        let reward = boosted.checked_div(scale.checked_mul(Uint128::new(86400))?)?;
+        Ok(match self.cap {
+            Some(cap) => reward.min(cap),
+            None => reward,
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -34,6 +86,9 @@ pub struct Dragon {
     pub unstaking_start_time: Uint64,
     pub unstaking_process: bool,
     pub reward_end_time: Uint64,
+    /// Experience settled at the last unstake. One xp is earned per second staked.
+    #[serde(default)]
+    pub xp: Uint64,
 }
 
 impl Dragon {
@@ -44,6 +99,157 @@ impl Dragon {
             Ok(Response::new())
         }
     }
+
+    /// Starts a new reward period after a claim. Once the unstaking process started the
+    /// dragon stops earning, so the period is closed instead.
+    pub fn reset_reward_period(&mut self, now: u64) {
+        if self.unstaking_process {
+            self.reward_start_time = Uint64::zero();
+            self.reward_end_time = Uint64::zero();
+        } else {
+            self.reward_start_time = Uint64::new(now);
+        }
+    }
+
+    /// Start and end of the period the pending reward was earned in.
+    pub fn reward_period(&self, now: u64) -> (Uint64, Uint64) {
+        // rewards stop accruing once the unstaking process starts
+        let end = if self.unstaking_process {
+            self.reward_end_time
+        } else {
+            Uint64::new(now)
+        };
+        (self.reward_start_time, end)
+    }
+
+    /// Starts a new ovulation period, after which the dragon can plant an egg or breed again.
+    pub fn start_ovulation(&mut self, now: u64) -> StdResult<()> {
+        //1 day -> 86400 seconds
+        let period_in_seconds = Uint64::new(self.ovulation_period).checked_mul(Uint64::new(86400))?;
+        self.hatch = period_in_seconds.checked_add(Uint64::new(now))?;
+        Ok(())
+    }
+
+    pub fn is_fertile(&self, now: u64) -> bool {
+        !self.hatch.is_zero() && self.hatch.u64() <= now
+    }
+
+    /// Settled xp plus the xp earned in the current staking period, which stops at the start
+    /// of unstaking.
+    pub fn current_xp(&self, now: u64) -> StdResult<u64> {
+        if !self.is_staked || self.stake_start_time.is_zero() {
+            return Ok(self.xp.u64());
+        }
+        let now = if self.unstaking_process {
+            now.min(self.unstaking_start_time.u64())
+        } else {
+            now
+        };
+        let staked = Uint64::new(now).checked_sub(self.stake_start_time)?;
+        Ok(self.xp.checked_add(staked)?.u64())
+    }
+}
+
+/// Reward multiplier unlocked once a dragon reaches `xp`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LevelThreshold {
+    pub xp: Uint64,
+    pub multiplier: Decimal,
+}
+
+/// Level thresholds in ascending xp order. A dragon below the first threshold is level 0 and
+/// earns the plain schedule reward. `unstake_xp_decay` is the share of xp lost on unstake.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct LevelConfig {
+    pub thresholds: Vec<LevelThreshold>,
+    pub unstake_xp_decay: Decimal,
+}
+
+impl LevelConfig {
+    /// Returns the level reached with `xp` and its reward multiplier.
+    pub fn level_for(&self, xp: u64) -> (u32, Decimal) {
+        self.thresholds
+            .iter()
+            .take_while(|threshold| threshold.xp.u64() <= xp)
+            .fold((0, Decimal::one()), |(level, _), threshold| {
+                (level + 1, threshold.multiplier)
+            })
+    }
+
+    pub fn next_threshold(&self, xp: u64) -> Option<&LevelThreshold> {
+        self.thresholds.iter().find(|threshold| threshold.xp.u64() > xp)
+    }
+
+    pub fn decayed_xp(&self, xp: u64) -> u64 {
+        (Uint128::from(xp) * (Decimal::one() - self.unstake_xp_decay)).u128() as u64
+    }
+}
+
+/// Emission mode pays `tokens_per_second` to all staked dragons together, split by the
+/// emission weight of their kind, instead of the per kind reward schedules.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct EmissionConfig {
+    pub enabled: bool,
+    pub tokens_per_second: Uint128,
+}
+
+/// Global reward-per-weight accumulator. It is kept up to date on every stake, unstake and
+/// claim, whether emission mode is enabled or not, so the mode can be switched at any time.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct EmissionState {
+    pub reward_per_weight: Decimal,
+    pub total_weight: Uint128,
+    pub last_update: Uint64,
+}
+
+/// Emission share of a staked dragon. `weight` is fixed when the dragon is staked and drops to
+/// zero once its unstaking process starts; `pending` holds what was earned until then.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DragonEmission {
+    pub weight: Uint128,
+    pub reward_per_weight_paid: Decimal,
+    pub pending: Uint128,
+}
+
+/// Groups of operations that can be paused independently.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseGroup {
+    Mint,
+    /// Staking, starting the unstaking process and unstaking.
+    Stake,
+    Claim,
+    /// Planting eggs and breeding.
+    Plant,
+    /// Transfers, sends and burns.
+    Transfer,
+}
+
+impl PauseGroup {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PauseGroup::Mint => "mint",
+            PauseGroup::Stake => "stake",
+            PauseGroup::Claim => "claim",
+            PauseGroup::Plant => "plant",
+            PauseGroup::Transfer => "transfer",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseInfo {
+    pub reason: String,
+    /// The pause lifts by itself once expired.
+    pub expires: Option<Expiration>,
+}
+
+/// Consent given by a dragon's owner to breed it with `partner_id`. It is only honoured while
+/// `owner` still owns the dragon.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BreedingConsent {
+    pub owner: String,
+    pub partner_id: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -60,6 +266,7 @@ pub struct DragonResponse {
     pub unstaking_start_time: Uint64,
     pub unstaking_process: bool,
     pub reward_end_time: Uint64,
+    pub xp: Uint64,
 }
 
 impl Into<DragonResponse> for Dragon {
@@ -77,6 +284,7 @@ impl Into<DragonResponse> for Dragon {
             unstaking_start_time: self.unstaking_start_time,
             unstaking_process: self.unstaking_process,
             reward_end_time: self.reward_end_time,
+            xp: self.xp,
         }
     }
 }
@@ -88,6 +296,137 @@ pub struct DragonListResponse {
 
 pub const STATE: Item<State> = Item::new("state");
 pub const COLLECTION_INFO: Item<CollectionInfo> = Item::new("collection_info");
-pub const DRAGON_INFO: Map<u64, Dragon> = Map::new("dragons_list");
 pub const DRAGON_INFO_SEQ: Item<Uint64> = Item::new("dragon_list_length");
 pub const MIN_STAKE_TIME: Item<Uint64> = Item::new("minimum_stake_time");
+pub const REWARD_SCHEDULES: Map<&str, RewardSchedule> = Map::new("reward_schedules");
+pub const LEVEL_CONFIG: Item<LevelConfig> = Item::new("level_config");
+pub const EMISSION_CONFIG: Item<EmissionConfig> = Item::new("emission_config");
+pub const EMISSION_STATE: Item<EmissionState> = Item::new("emission_state");
+pub const EMISSION_WEIGHTS: Map<&str, Uint128> = Map::new("emission_weights");
+pub const DRAGON_EMISSIONS: Map<u64, DragonEmission> = Map::new("dragon_emissions");
+/// Claims sent to the reward contract whose reply has not been handled yet, by reply id.
+pub const PENDING_CLAIMS: Map<u64, Claim> = Map::new("pending_claims");
+/// Rewards that could not be paid out by the reward contract, by recipient.
+pub const OWED: Map<&Addr, Uint128> = Map::new("owed");
+pub const GUARDIAN: Item<Addr> = Item::new("guardian");
+/// Addresses allowed to mint besides the cw721 minter.
+pub const MINTERS: Map<&Addr, Empty> = Map::new("minters");
+pub const PAUSES: Map<&str, PauseInfo> = Map::new("pauses");
+pub const BREEDING_FEE: Item<Coin> = Item::new("breeding_fee");
+pub const BREEDING_CONSENTS: Map<u64, BreedingConsent> = Map::new("breeding_consents");
+pub const BREEDING_SEQ: Item<u64> = Item::new("breeding_seq");
+/// Numbers planted eggs, whose birth ids must be unique in egg-mint.
+pub const PLANT_SEQ: Item<u64> = Item::new("plant_seq");
+pub const CLAIM_HISTORY_SEQ: Item<u64> = Item::new("claim_history_seq");
+/// Lifetime rewards paid out per dragon and per owner.
+pub const DRAGON_CLAIM_TOTALS: Map<u64, Uint128> = Map::new("dragon_claim_totals");
+pub const OWNER_CLAIM_TOTALS: Map<&str, Uint128> = Map::new("owner_claim_totals");
+/// Whether an owner has a dragon staked, kept for every block so past heights can be queried.
+/// Unstaking stores `false` rather than removing the entry, so the entries under an owner are
+/// every dragon it ever staked.
+pub const STAKED_DRAGONS: SnapshotMap<(&str, u64), bool> = SnapshotMap::new(
+    "staked_dragons",
+    "staked_dragons__checkpoints",
+    "staked_dragons__changelog",
+    Strategy::EveryBlock,
+);
+pub const TOTAL_STAKED: SnapshotItem<u64> = SnapshotItem::new(
+    "total_staked",
+    "total_staked__checkpoints",
+    "total_staked__changelog",
+    Strategy::EveryBlock,
+);
+/// Staked dragon counts by owner and kind, and by kind, that voting power is derived from.
+pub const OWNER_KIND_STAKED: SnapshotMap<(&str, &str), u64> = SnapshotMap::new(
+    "owner_kind_staked",
+    "owner_kind_staked__checkpoints",
+    "owner_kind_staked__changelog",
+    Strategy::EveryBlock,
+);
+pub const KIND_STAKED: SnapshotMap<&str, u64> = SnapshotMap::new(
+    "kind_staked",
+    "kind_staked__checkpoints",
+    "kind_staked__changelog",
+    Strategy::EveryBlock,
+);
+/// Voting power of a staked dragon by kind, 1 when unset.
+pub const VOTING_WEIGHTS: SnapshotMap<&str, Uint128> = SnapshotMap::new(
+    "voting_weights",
+    "voting_weights__checkpoints",
+    "voting_weights__changelog",
+    Strategy::EveryBlock,
+);
+
+pub struct DragonIndexes<'a> {
+    pub owner: MultiIndex<'a, String, Dragon, u64>,
+    pub is_staked: MultiIndex<'a, u8, Dragon, u64>,
+    pub kind: MultiIndex<'a, String, Dragon, u64>,
+}
+
+impl<'a> IndexList<Dragon> for DragonIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Dragon>> + '_> {
+        let v: Vec<&dyn Index<Dragon>> = vec![&self.owner, &self.is_staked, &self.kind];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn dragon_info<'a>() -> IndexedMap<'a, u64, Dragon, DragonIndexes<'a>> {
+    let indexes = DragonIndexes {
+        owner: MultiIndex::new(
+            |d: &Dragon| d.owner.clone(),
+            "dragons_list",
+            "dragons_list__owner",
+        ),
+        is_staked: MultiIndex::new(
+            |d: &Dragon| d.is_staked as u8,
+            "dragons_list",
+            "dragons_list__is_staked",
+        ),
+        kind: MultiIndex::new(
+            |d: &Dragon| d.kind.clone(),
+            "dragons_list",
+            "dragons_list__kind",
+        ),
+    };
+    IndexedMap::new("dragons_list", indexes)
+}
+
+/// A reward paid out for one dragon. Entries are only ever appended.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimRecord {
+    pub id: u64,
+    pub token_id: Uint64,
+    pub owner: String,
+    pub amount: Uint128,
+    pub period_start: Uint64,
+    pub period_end: Uint64,
+    pub height: u64,
+}
+
+pub struct ClaimRecordIndexes<'a> {
+    pub token_id: MultiIndex<'a, u64, ClaimRecord, u64>,
+    pub owner: MultiIndex<'a, String, ClaimRecord, u64>,
+}
+
+impl<'a> IndexList<ClaimRecord> for ClaimRecordIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<ClaimRecord>> + '_> {
+        let v: Vec<&dyn Index<ClaimRecord>> = vec![&self.token_id, &self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn claim_history<'a>() -> IndexedMap<'a, u64, ClaimRecord, ClaimRecordIndexes<'a>> {
+    let indexes = ClaimRecordIndexes {
+        token_id: MultiIndex::new(
+            |c: &ClaimRecord| c.token_id.u64(),
+            "claim_history",
+            "claim_history__token_id",
+        ),
+        owner: MultiIndex::new(
+            |c: &ClaimRecord| c.owner.clone(),
+            "claim_history",
+            "claim_history__owner",
+        ),
+    };
+    IndexedMap::new("claim_history", indexes)
+}
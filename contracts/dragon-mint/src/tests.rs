@@ -0,0 +1,2361 @@
+use crate::contract::{execute, instantiate, migrate, query, reply};
+use crate::msg::{
+    AuthorizedMintersResponse, ClaimHistoryResponse, ClaimMessage, CustomMintMsg,
+    DragonBirthWrapper, DragonParent, EmissionsResponse, ExecuteMsg, InstantiateMsg, KindCount,
+    KindRewardSchedule, Metadata, MigrateMsg, OwedResponse, PauseStatusResponse, PortfolioResponse,
+    QueryMsg, RewardSchedulesResponse, StakedAtResponse, StateResponse, TotalPowerAtHeightResponse,
+    TotalStakedAtResponse, Trait, VotingPowerAtHeightResponse, VotingWeightsResponse,
+};
+use crate::reward::{DragonLevelResponse, RewardBreakdown};
+use crate::state::{
+    dragon_info, BreedingConsent, ClaimRecord, Dragon, DragonListResponse, DragonResponse,
+    EmissionConfig, LevelConfig, LevelThreshold, PauseGroup, RewardSchedule, REWARD_SCHEDULES,
+};
+use crate::ContractError;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{
+    coin, coins, from_binary, to_binary, Addr, BankMsg, Binary, CosmosMsg, Decimal, Deps, DepsMut,
+    Empty, Env, MessageInfo, Response, StdError, StdResult, Uint128, Uint64, WasmMsg,
+};
+use cw2::{get_contract_version, set_contract_version};
+use cw721::{Cw721ReceiveMsg, NftInfoResponse, OwnerOfResponse};
+use cw721_base::msg::InstantiateMsg as Cw721InstantiateMsg;
+use cw721_base::{ContractError as Cw721ContractError, MintMsg};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_storage_plus::Map;
+use cw_utils::Expiration;
+use proptest::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use stake_dragons_utils::migrate::MigrateError;
+use stake_dragons_utils::ownership::{OwnershipError, PendingOwnerResponse};
+use std::str::FromStr;
+
+const OWNER: &str = "owner";
+const MINTER: &str = "minter";
+
+fn schedule(rate: u128, precision: u32, cap: Option<u128>) -> RewardSchedule {
+    RewardSchedule {
+        rate: Uint128::new(rate),
+        precision,
+        cap: cap.map(Uint128::new),
+    }
+}
+
+fn setup_contract(deps: DepsMut) {
+    let msg = InstantiateMsg {
+        base: Cw721InstantiateMsg {
+            name: "STAKE DRAGONS".to_string(),
+            symbol: "DRGN".to_string(),
+            minter: MINTER.to_string(),
+        },
+        size: Uint64::new(100),
+        base_price: Uint64::new(1),
+        reward_contract_address: "reward".to_string(),
+        reward_schedules: vec![
+            KindRewardSchedule {
+                kind: "common".to_string(),
+                schedule: schedule(500000, 0, None),
+            },
+            KindRewardSchedule {
+                kind: "rare".to_string(),
+                schedule: schedule(3000000, 0, None),
+            },
+        ],
+        breeding_fee: Some(coin(100, "ujuno")),
+    };
+    instantiate(deps, mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+}
+
+fn mint_msg(owner: &str, kind: &str) -> ExecuteMsg {
+    let traits = vec![
+        ("kind", kind),
+        ("ovulation_period", "1"),
+        ("daily_income", "1"),
+    ];
+    ExecuteMsg::Mint(CustomMintMsg {
+        base: MintMsg {
+            token_id: String::new(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        },
+        extension: traits
+            .into_iter()
+            .map(|(trait_type, value)| Trait {
+                display_type: None,
+                trait_type: trait_type.to_string(),
+                value: value.to_string(),
+            })
+            .collect(),
+    })
+}
+
+fn env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(seconds);
+    env
+}
+
+fn execute_ok(deps: DepsMut, env: Env, sender: &str, msg: ExecuteMsg) -> Response {
+    execute(deps, env, mock_info(sender, &[]), msg).unwrap()
+}
+
+fn sent_claim(res: &Response) -> ClaimMessage {
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => from_binary(msg).unwrap(),
+        msg => panic!("unexpected message {:?}", msg),
+    }
+}
+
+/// Zero rewards are not sent to the reward contract.
+fn claimed_amount(res: &Response) -> Uint128 {
+    if res.messages.is_empty() {
+        return Uint128::zero();
+    }
+    sent_claim(res).claim.amount
+}
+
+fn query_reward(deps: Deps, env: Env, token_id: u64) -> RewardBreakdown {
+    from_binary(
+        &query(
+            deps,
+            env,
+            QueryMsg::CalculateReward {
+                token_id: Uint64::new(token_id),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn reward_schedule_math() {
+    let common = schedule(500000, 0, None);
+    assert_eq!(common.reward_for(86400).unwrap(), Uint128::new(500000));
+    assert_eq!(common.reward_for(43200).unwrap(), Uint128::new(250000));
+
+    let precise = schedule(15, 1, None);
+    assert_eq!(precise.reward_for(86400 * 10).unwrap(), Uint128::new(15));
+
+    let capped = schedule(1000000, 0, Some(1500000));
+    assert_eq!(capped.reward_for(86400 * 3).unwrap(), Uint128::new(1500000));
+}
+
+#[test]
+fn owner_manages_reward_schedules() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+
+    let set_msg = ExecuteMsg::SetRewardSchedule {
+        kind: "legendary".to_string(),
+        schedule: schedule(5000000, 0, Some(100000000)),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        set_msg.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), set_msg).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::SetRewardSchedule {
+            kind: "epic".to_string(),
+            schedule: schedule(1, 19, None),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidRewardSchedule { .. }));
+
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        MINTER,
+        mint_msg("alice", "legendary"),
+    );
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::RemoveRewardSchedule {
+            kind: "legendary".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::KindInUse { kind } if kind == "legendary"));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::RemoveRewardSchedule {
+            kind: "rare".to_string(),
+        },
+    )
+    .unwrap();
+
+    let res: RewardSchedulesResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::RewardSchedules {}).unwrap())
+            .unwrap();
+    let kinds: Vec<String> = res.schedules.into_iter().map(|s| s.kind).collect();
+    assert_eq!(kinds, vec!["common".to_string(), "legendary".to_string()]);
+}
+
+#[test]
+fn ownership_handover_requires_acceptance() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    let propose = |expires: Option<Expiration>| ExecuteMsg::ProposeOwner {
+        new_owner: "new_owner".to_string(),
+        expires,
+    };
+    let accept = |deps: DepsMut, env: Env, sender: &str| {
+        execute(
+            deps,
+            env,
+            mock_info(sender, &[]),
+            ExecuteMsg::AcceptOwnership {},
+        )
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        propose(None),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    let deadline = Expiration::AtHeight(mock_env().block.height + 10);
+    execute_ok(deps.as_mut(), mock_env(), OWNER, propose(Some(deadline)));
+    let pending: PendingOwnerResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PendingOwner {}).unwrap()).unwrap();
+    assert_eq!(pending.pending_owner, Some("new_owner".to_string()));
+    assert_eq!(pending.expires, Some(deadline));
+
+    let err = accept(deps.as_mut(), mock_env(), "mallory").unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Ownership(OwnershipError::Unauthorized {})
+    ));
+    let mut late = mock_env();
+    late.block.height += 10;
+    let err = accept(deps.as_mut(), late, "new_owner").unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Ownership(OwnershipError::OwnershipProposalExpired {})
+    ));
+
+    execute_ok(deps.as_mut(), mock_env(), OWNER, propose(None));
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        OWNER,
+        ExecuteMsg::CancelOwnershipProposal {},
+    );
+    let err = accept(deps.as_mut(), mock_env(), "new_owner").unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Ownership(OwnershipError::NoPendingOwner {})
+    ));
+
+    execute_ok(deps.as_mut(), mock_env(), OWNER, propose(None));
+    accept(deps.as_mut(), mock_env(), "new_owner").unwrap();
+    let state: StateResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap()).unwrap();
+    assert_eq!(state.owner, "new_owner");
+    let pending: PendingOwnerResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PendingOwner {}).unwrap()).unwrap();
+    assert_eq!(pending.pending_owner, None);
+}
+
+#[test]
+fn mint_rejects_unknown_kind() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MINTER, &[]),
+        mint_msg("alice", "epic"),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::KindNotFound {}));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MINTER, &[]),
+        mint_msg("alice", "rare"),
+    )
+    .unwrap();
+}
+
+#[test]
+fn mint_validates_traits_and_mirrors_them_in_metadata() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    let mint_with = |traits: &[(&str, &str)]| {
+        let mut msg = match mint_msg("alice", "rare") {
+            ExecuteMsg::Mint(msg) => msg,
+            _ => unreachable!(),
+        };
+        msg.extension = traits
+            .iter()
+            .map(|(trait_type, value)| Trait {
+                display_type: None,
+                trait_type: trait_type.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+        ExecuteMsg::Mint(msg)
+    };
+    let mint_err = |deps: DepsMut, msg: ExecuteMsg| {
+        execute(deps, mock_env(), mock_info(MINTER, &[]), msg).unwrap_err()
+    };
+
+    let err = mint_err(deps.as_mut(), mint_msg("alice", "Mythic"));
+    assert!(matches!(
+        err,
+        ContractError::InvalidTraitValue { ref trait_type, ref value }
+            if trait_type == "kind" && value == "Mythic"
+    ));
+    let err = mint_err(deps.as_mut(), mint_msg("alice", "mythic"));
+    assert!(matches!(err, ContractError::KindNotFound {}));
+    let err = mint_err(
+        deps.as_mut(),
+        mint_with(&[
+            ("kind", "rare"),
+            ("ovulation_period", "soon"),
+            ("daily_income", "1"),
+        ]),
+    );
+    assert!(matches!(
+        err,
+        ContractError::InvalidTraitValue { ref trait_type, ref value }
+            if trait_type == "ovulation_period" && value == "soon"
+    ));
+    let err = mint_err(
+        deps.as_mut(),
+        mint_with(&[
+            ("kind", "rare"),
+            ("ovulation_period", "0"),
+            ("daily_income", "1"),
+        ]),
+    );
+    assert!(matches!(err, ContractError::InvalidTraitValue { .. }));
+    let err = mint_err(
+        deps.as_mut(),
+        mint_with(&[("kind", "rare"), ("daily_income", "1")]),
+    );
+    assert!(matches!(
+        err,
+        ContractError::MissingTrait { ref trait_type } if trait_type == "ovulation_period"
+    ));
+    let err = mint_err(
+        deps.as_mut(),
+        mint_with(&[
+            ("kind", "rare"),
+            ("ovulation_period", "3"),
+            ("kind", "common"),
+            ("daily_income", "1"),
+        ]),
+    );
+    assert!(matches!(
+        err,
+        ContractError::DuplicateTrait { ref trait_type } if trait_type == "kind"
+    ));
+
+    let mut msg = match mint_with(&[
+        ("daily_income", "25"),
+        ("kind", "rare"),
+        ("ovulation_period", "3"),
+    ]) {
+        ExecuteMsg::Mint(msg) => msg,
+        _ => unreachable!(),
+    };
+    msg.base.extension = Some(Metadata {
+        name: Some("Ember".to_string()),
+        attributes: vec![
+            Trait {
+                display_type: None,
+                trait_type: "kind".to_string(),
+                value: "legendary".to_string(),
+            },
+            Trait {
+                display_type: None,
+                trait_type: "color".to_string(),
+                value: "red".to_string(),
+            },
+        ],
+        ..Metadata::default()
+    });
+    execute_ok(deps.as_mut(), mock_env(), MINTER, ExecuteMsg::Mint(msg));
+
+    let dragon = query_dragon(deps.as_ref(), 1);
+    assert_eq!(dragon.kind, "rare");
+    assert_eq!(dragon.ovulation_period, 3);
+    let info: NftInfoResponse<Metadata> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftInfo {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let attributes: Vec<(&str, &str)> = info
+        .extension
+        .attributes
+        .iter()
+        .map(|attr| (attr.trait_type.as_str(), attr.value.as_str()))
+        .collect();
+    assert_eq!(info.extension.name, Some("Ember".to_string()));
+    assert_eq!(
+        attributes,
+        vec![
+            ("color", "red"),
+            ("kind", "rare"),
+            ("ovulation_period", "3"),
+            ("daily_income", "25"),
+        ]
+    );
+
+    // a new kind only needs a reward schedule
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        OWNER,
+        ExecuteMsg::SetRewardSchedule {
+            kind: "mythic".to_string(),
+            schedule: schedule(1, 0, None),
+        },
+    );
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        MINTER,
+        mint_msg("alice", "mythic"),
+    );
+    assert_eq!(query_dragon(deps.as_ref(), 2).kind, "mythic");
+}
+
+#[test]
+fn mint_requires_authorized_minter_and_respects_size() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    let mint = |deps: DepsMut, sender: &str| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            mint_msg("alice", "rare"),
+        )
+    };
+
+    let err = mint(deps.as_mut(), "egg_mint").unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    let add_minter = ExecuteMsg::AddMinter {
+        address: "egg_mint".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("mallory", &[]),
+        add_minter.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    execute_ok(deps.as_mut(), mock_env(), OWNER, add_minter);
+    let minters: AuthorizedMintersResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::AuthorizedMinters {}).unwrap())
+            .unwrap();
+    assert_eq!(minters.minters, vec!["egg_mint".to_string()]);
+
+    mint(deps.as_mut(), "egg_mint").unwrap();
+    let owner: OwnerOfResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::OwnerOf {
+                token_id: "1".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(owner.owner, "alice");
+
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        OWNER,
+        ExecuteMsg::RemoveMinter {
+            address: "egg_mint".to_string(),
+        },
+    );
+    let err = mint(deps.as_mut(), "egg_mint").unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    // the collection holds 100 dragons
+    for _ in 1..100 {
+        mint(deps.as_mut(), MINTER).unwrap();
+    }
+    let err = mint(deps.as_mut(), MINTER).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidSize {}));
+}
+
+#[test]
+fn calculate_reward_reads_schedule() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MINTER, &[]),
+        mint_msg("alice", "rare"),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::StakeDragon {
+            token_id: Uint64::new(1),
+        },
+    )
+    .unwrap();
+
+    let reward = query_reward(deps.as_ref(), env_at(86400), 1);
+    assert_eq!(
+        reward,
+        RewardBreakdown {
+            elapsed_seconds: Uint64::new(86400),
+            rate: Uint128::new(3000000),
+            precision: 0,
+            level: 0,
+            multiplier: Decimal::one(),
+            emission_weight: None,
+            amount: Uint128::new(3000000),
+        }
+    );
+}
+
+#[test]
+fn cancel_unstaking_resumes_rewards_without_losing_accrued() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    execute_ok(deps.as_mut(), mock_env(), MINTER, mint_msg("alice", "rare"));
+    let token_id = Uint64::new(1);
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        "alice",
+        ExecuteMsg::StakeDragon { token_id },
+    );
+    let hatch = query_dragon(deps.as_ref(), 1).hatch;
+
+    let cancel = ExecuteMsg::CancelUnstaking { token_id };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        cancel.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::UnstakingProcessIsNotStarted {}
+    ));
+
+    execute_ok(
+        deps.as_mut(),
+        env_at(86400),
+        "alice",
+        ExecuteMsg::StartUnstakingProcess { token_id },
+    );
+    let err = execute(
+        deps.as_mut(),
+        env_at(3 * 86400),
+        mock_info("bob", &[]),
+        cancel.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    execute_ok(deps.as_mut(), env_at(3 * 86400), "alice", cancel);
+
+    // one day before unstaking plus one day after cancelling, the two days in between are lost
+    let reward = query_reward(deps.as_ref(), env_at(4 * 86400), 1);
+    assert_eq!(reward.elapsed_seconds, Uint64::new(2 * 86400));
+    assert_eq!(reward.amount, Uint128::new(6000000));
+    let dragon = query_dragon(deps.as_ref(), 1);
+    assert!(dragon.is_staked);
+    assert!(!dragon.unstaking_process);
+    assert_eq!(dragon.hatch, hatch);
+
+    execute_ok(
+        deps.as_mut(),
+        env_at(4 * 86400),
+        "alice",
+        ExecuteMsg::StartUnstakingProcess { token_id },
+    );
+}
+
+#[test]
+fn claims_are_recorded_in_history() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    for kind in ["common", "rare"] {
+        execute_ok(deps.as_mut(), mock_env(), MINTER, mint_msg("alice", kind));
+    }
+    for id in [1, 2] {
+        execute_ok(
+            deps.as_mut(),
+            mock_env(),
+            "alice",
+            ExecuteMsg::StakeDragon {
+                token_id: Uint64::new(id),
+            },
+        );
+    }
+    let start = mock_env().block.time.seconds();
+
+    execute_ok(
+        deps.as_mut(),
+        env_at(86400),
+        "alice",
+        ExecuteMsg::ClaimReward {
+            token_id: Uint64::new(1),
+        },
+    );
+    // forfeited rewards are not paid, so they are not recorded
+    execute_ok(
+        deps.as_mut(),
+        env_at(86400),
+        "alice",
+        ExecuteMsg::Claim {
+            token_id: Uint64::new(2),
+        },
+    );
+    execute_ok(
+        deps.as_mut(),
+        env_at(2 * 86400),
+        "alice",
+        ExecuteMsg::ClaimAllRewards {
+            start_after: None,
+            limit: None,
+        },
+    );
+
+    let history = |deps: Deps, msg: QueryMsg| -> ClaimHistoryResponse {
+        from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+    };
+    let dragon_history = history(
+        deps.as_ref(),
+        QueryMsg::ClaimHistory {
+            token_id: Uint64::new(1),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(dragon_history.total_claimed, Uint128::new(1000000));
+    assert_eq!(
+        dragon_history.claims[0],
+        ClaimRecord {
+            id: 1,
+            token_id: Uint64::new(1),
+            owner: "alice".to_string(),
+            amount: Uint128::new(500000),
+            period_start: Uint64::new(start),
+            period_end: Uint64::new(start + 86400),
+            height: mock_env().block.height,
+        }
+    );
+    assert_eq!(
+        dragon_history.claims[1].period_start,
+        Uint64::new(start + 86400)
+    );
+    assert_eq!(dragon_history.claims.len(), 2);
+
+    let owner_history = history(
+        deps.as_ref(),
+        QueryMsg::OwnerClaimHistory {
+            owner: "alice".to_string(),
+            start_after: Some(1),
+            limit: Some(1),
+        },
+    );
+    assert_eq!(owner_history.total_claimed, Uint128::new(4000000));
+    assert_eq!(owner_history.claims.len(), 1);
+    assert_eq!(owner_history.claims[0].id, 2);
+    assert_eq!(owner_history.claims[0].token_id, Uint64::new(1));
+    let last = history(
+        deps.as_ref(),
+        QueryMsg::OwnerClaimHistory {
+            owner: "alice".to_string(),
+            start_after: Some(2),
+            limit: None,
+        },
+    );
+    assert_eq!(last.claims.len(), 1);
+    assert_eq!(last.claims[0].token_id, Uint64::new(2));
+    assert_eq!(last.claims[0].amount, Uint128::new(3000000));
+}
+
+#[test]
+fn claim_all_rewards_aggregates_and_pages() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    for (owner, kind) in [
+        ("alice", "common"),
+        ("bob", "rare"),
+        ("alice", "rare"),
+        ("alice", "rare"),
+    ] {
+        execute_ok(deps.as_mut(), mock_env(), MINTER, mint_msg(owner, kind));
+    }
+    for (owner, id) in [("alice", 1), ("bob", 2), ("alice", 3)] {
+        execute_ok(
+            deps.as_mut(),
+            mock_env(),
+            owner,
+            ExecuteMsg::StakeDragon {
+                token_id: Uint64::new(id),
+            },
+        );
+    }
+
+    // first page only covers dragon 1
+    let res = execute_ok(
+        deps.as_mut(),
+        env_at(86400),
+        "alice",
+        ExecuteMsg::ClaimAllRewards {
+            start_after: None,
+            limit: Some(1),
+        },
+    );
+    assert_eq!(res.messages.len(), 1);
+    let claim = sent_claim(&res).claim;
+    assert_eq!(claim.recipient, "alice");
+    assert_eq!(claim.amount, Uint128::new(500000));
+
+    // the next page skips bob's dragon and the unstaked dragon 4
+    let res = execute_ok(
+        deps.as_mut(),
+        env_at(86400),
+        "alice",
+        ExecuteMsg::ClaimAllRewards {
+            start_after: Some(1),
+            limit: None,
+        },
+    );
+    assert_eq!(sent_claim(&res).claim.amount, Uint128::new(3000000));
+
+    // all periods were reset, a full claim one day later sums both dragons
+    let res = execute_ok(
+        deps.as_mut(),
+        env_at(2 * 86400),
+        "alice",
+        ExecuteMsg::ClaimAllRewards {
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(sent_claim(&res).claim.amount, Uint128::new(3500000));
+    assert_eq!(
+        query_reward(deps.as_ref(), env_at(2 * 86400), 2).amount,
+        Uint128::new(6000000)
+    );
+
+    // nothing accrued, nothing is sent
+    let res = execute_ok(
+        deps.as_mut(),
+        env_at(2 * 86400),
+        "alice",
+        ExecuteMsg::ClaimAllRewards {
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert!(res.messages.is_empty());
+
+    // a dragon without a schedule is skipped and the rest of the page is still paid
+    REWARD_SCHEDULES.remove(deps.as_mut().storage, "rare");
+    let res = execute_ok(
+        deps.as_mut(),
+        env_at(3 * 86400),
+        "alice",
+        ExecuteMsg::ClaimAllRewards {
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(sent_claim(&res).claim.amount, Uint128::new(500000));
+    let attr = |key: &str| {
+        res.attributes
+            .iter()
+            .find(|a| a.key == key)
+            .map(|a| a.value.clone())
+    };
+    assert_eq!(attr("claimed_dragons"), Some("1".to_string()));
+    assert_eq!(attr("skipped_dragons"), Some("3".to_string()));
+    assert_eq!(
+        query_dragon(deps.as_ref(), 3).reward_start_time,
+        Uint64::new(env_at(2 * 86400).block.time.seconds())
+    );
+}
+
+fn query_dragon(deps: Deps, token_id: u64) -> DragonResponse {
+    let msg = QueryMsg::DragonInfo {
+        id: Uint64::new(token_id),
+    };
+    from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+}
+
+#[test]
+fn breeding_requires_fertile_parents_consent_and_fee() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    for (owner, kind) in [("alice", "common"), ("bob", "rare"), ("alice", "common")] {
+        execute_ok(deps.as_mut(), mock_env(), MINTER, mint_msg(owner, kind));
+    }
+    for (owner, token_id) in [("alice", 1), ("bob", 2), ("alice", 3)] {
+        let msg = ExecuteMsg::StakeDragon {
+            token_id: Uint64::new(token_id),
+        };
+        execute_ok(deps.as_mut(), mock_env(), owner, msg);
+    }
+    let breed = |token_id: u64, partner_id: u64| ExecuteMsg::BreedDragons {
+        token_id: Uint64::new(token_id),
+        partner_id: Uint64::new(partner_id),
+    };
+    let fee = coins(100, "ujuno");
+
+    // the ovulation period of one day has not ended yet
+    let err = execute(
+        deps.as_mut(),
+        env_at(86399),
+        mock_info("alice", &fee),
+        breed(1, 3),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::OvulationInProgress {}));
+
+    let err = execute(
+        deps.as_mut(),
+        env_at(86400),
+        mock_info("alice", &fee),
+        breed(1, 2),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::BreedingConsentNotFound { .. }));
+
+    let approve = ExecuteMsg::ApproveBreeding {
+        token_id: Uint64::new(2),
+        partner_id: Uint64::new(1),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        approve.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    execute_ok(deps.as_mut(), mock_env(), "bob", approve);
+
+    let err = execute(
+        deps.as_mut(),
+        env_at(86400),
+        mock_info("alice", &coins(10, "ujuno")),
+        breed(1, 2),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidBreedingFee { .. }));
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(86400),
+        mock_info("alice", &fee),
+        breed(1, 2),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: OWNER.to_string(),
+            amount: fee.clone(),
+        })
+    );
+    let birth: DragonBirthWrapper = match &res.messages[1].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) => {
+            assert_eq!(contract_addr, MINTER);
+            from_binary(msg).unwrap()
+        }
+        msg => panic!("unexpected message {:?}", msg),
+    };
+    assert_eq!(birth.dragon_birth.id, "breed1");
+    assert_eq!(birth.dragon_birth.owner, "alice");
+    assert_eq!(
+        birth.dragon_birth.parents,
+        vec![
+            DragonParent {
+                token_id: "1".to_string(),
+                kind: "common".to_string(),
+            },
+            DragonParent {
+                token_id: "2".to_string(),
+                kind: "rare".to_string(),
+            },
+        ]
+    );
+    let next_hatch = env_at(86400 * 2).block.time.seconds();
+    assert_eq!(query_dragon(deps.as_ref(), 1).hatch.u64(), next_hatch);
+    assert_eq!(query_dragon(deps.as_ref(), 2).hatch.u64(), next_hatch);
+
+    // the consent was used up and dragon 1 is ovulating again
+    let consent: Option<BreedingConsent> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BreedingConsent {
+                token_id: Uint64::new(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(consent, None);
+    let err = execute(
+        deps.as_mut(),
+        env_at(86400),
+        mock_info("alice", &fee),
+        breed(3, 1),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::OvulationInProgress {}));
+}
+
+#[test]
+fn breeding_rejects_unstaked_dragons() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    for _ in 0..2 {
+        execute_ok(
+            deps.as_mut(),
+            mock_env(),
+            MINTER,
+            mint_msg("alice", "common"),
+        );
+    }
+    let stake = ExecuteMsg::StakeDragon {
+        token_id: Uint64::new(1),
+    };
+    execute_ok(deps.as_mut(), mock_env(), "alice", stake);
+
+    let fee = coins(100, "ujuno");
+    for (token_id, partner_id) in [(1, 2), (2, 1)] {
+        let breed = ExecuteMsg::BreedDragons {
+            token_id: Uint64::new(token_id),
+            partner_id: Uint64::new(partner_id),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(86400),
+            mock_info("alice", &fee),
+            breed,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::DragonNotStaked {}));
+    }
+}
+
+fn query_level(deps: Deps, env: Env, token_id: u64) -> DragonLevelResponse {
+    let msg = QueryMsg::DragonLevel {
+        token_id: Uint64::new(token_id),
+    };
+    from_binary(&query(deps, env, msg).unwrap()).unwrap()
+}
+
+#[test]
+fn dragon_level_boosts_reward_and_decays_on_unstake() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    let threshold = |days: u64, multiplier: &str| LevelThreshold {
+        xp: Uint64::new(86400 * days),
+        multiplier: Decimal::from_str(multiplier).unwrap(),
+    };
+    let config = LevelConfig {
+        thresholds: vec![threshold(1, "1.5"), threshold(3, "2")],
+        unstake_xp_decay: Decimal::percent(50),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::UpdateLevelConfig {
+            config: config.clone(),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    let unordered = LevelConfig {
+        thresholds: vec![threshold(3, "2"), threshold(1, "1.5")],
+        unstake_xp_decay: Decimal::zero(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateLevelConfig { config: unordered },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidLevelConfig { .. }));
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        OWNER,
+        ExecuteMsg::UpdateLevelConfig { config },
+    );
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        OWNER,
+        ExecuteMsg::UpdateMinStakeTime {
+            time: Uint64::zero(),
+        },
+    );
+
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        MINTER,
+        mint_msg("alice", "common"),
+    );
+    let token_id = Uint64::new(1);
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        "alice",
+        ExecuteMsg::StakeDragon { token_id },
+    );
+
+    let level = query_level(deps.as_ref(), env_at(43200), 1);
+    assert_eq!(level.level, 0);
+    assert_eq!(level.multiplier, Decimal::one());
+    assert_eq!(level.next_level_xp, Some(Uint64::new(86400)));
+    assert_eq!(level.seconds_to_next_level, Some(Uint64::new(43200)));
+
+    // the first day pays the base rate, the second one is boosted
+    let reward = query_reward(deps.as_ref(), env_at(86400 * 2), 1);
+    assert_eq!(reward.level, 1);
+    assert_eq!(reward.amount, Uint128::new(1250000));
+
+    execute_ok(
+        deps.as_mut(),
+        env_at(86400 * 2),
+        "alice",
+        ExecuteMsg::StartUnstakingProcess { token_id },
+    );
+    execute_ok(
+        deps.as_mut(),
+        env_at(86400 * 2),
+        "alice",
+        ExecuteMsg::UnstakeDragon { token_id },
+    );
+    let level = query_level(deps.as_ref(), env_at(86400 * 5), 1);
+    assert_eq!(level.xp, Uint64::new(86400));
+    assert_eq!(level.level, 1);
+    assert_eq!(level.seconds_to_next_level, None);
+}
+
+#[test]
+fn xp_stops_while_unstaking() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    let config = LevelConfig {
+        thresholds: vec![LevelThreshold {
+            xp: Uint64::new(86400),
+            multiplier: Decimal::from_str("1.5").unwrap(),
+        }],
+        unstake_xp_decay: Decimal::zero(),
+    };
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        OWNER,
+        ExecuteMsg::UpdateLevelConfig { config },
+    );
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        OWNER,
+        ExecuteMsg::UpdateMinStakeTime {
+            time: Uint64::zero(),
+        },
+    );
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        MINTER,
+        mint_msg("alice", "common"),
+    );
+    let token_id = Uint64::new(1);
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        "alice",
+        ExecuteMsg::StakeDragon { token_id },
+    );
+    execute_ok(
+        deps.as_mut(),
+        env_at(43200),
+        "alice",
+        ExecuteMsg::StartUnstakingProcess { token_id },
+    );
+
+    let level = query_level(deps.as_ref(), env_at(86400 * 3), 1);
+    assert_eq!(level.xp, Uint64::new(43200));
+    assert_eq!(level.level, 0);
+    assert_eq!(level.seconds_to_next_level, None);
+
+    execute_ok(
+        deps.as_mut(),
+        env_at(86400 * 3),
+        "alice",
+        ExecuteMsg::CancelUnstaking { token_id },
+    );
+    let level = query_level(deps.as_ref(), env_at(86400 * 3 + 43200), 1);
+    assert_eq!(level.xp, Uint64::new(86400));
+    assert_eq!(level.level, 1);
+
+    // a day at the base rate across the unstaking, then half a boosted day
+    let reward = query_reward(deps.as_ref(), env_at(86400 * 4), 1);
+    assert_eq!(reward.elapsed_seconds, Uint64::new(86400 + 43200));
+    assert_eq!(reward.amount, Uint128::new(875000));
+}
+
+#[test]
+fn emissions_split_budget_by_kind_weight() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        MINTER,
+        mint_msg("alice", "common"),
+    );
+    execute_ok(deps.as_mut(), mock_env(), MINTER, mint_msg("bob", "rare"));
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        OWNER,
+        ExecuteMsg::SetEmissionWeight {
+            kind: "rare".to_string(),
+            weight: Uint128::new(3),
+        },
+    );
+    let enable = ExecuteMsg::UpdateEmissionConfig {
+        config: EmissionConfig {
+            enabled: true,
+            tokens_per_second: Uint128::new(100),
+        },
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        enable.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    execute_ok(deps.as_mut(), mock_env(), OWNER, enable);
+
+    let stake = |token_id: u64| ExecuteMsg::StakeDragon {
+        token_id: Uint64::new(token_id),
+    };
+    execute_ok(deps.as_mut(), mock_env(), "alice", stake(1));
+    execute_ok(deps.as_mut(), env_at(100), "bob", stake(2));
+
+    // alice had the whole budget for 100 seconds, then a quarter of it
+    let alice = query_reward(deps.as_ref(), env_at(200), 1);
+    assert_eq!(alice.emission_weight, Some(Uint128::new(1)));
+    assert_eq!(alice.amount, Uint128::new(12500));
+    let bob = query_reward(deps.as_ref(), env_at(200), 2);
+    assert_eq!(bob.amount, Uint128::new(7500));
+
+    let res = execute_ok(
+        deps.as_mut(),
+        env_at(200),
+        "alice",
+        ExecuteMsg::ClaimReward {
+            token_id: Uint64::new(1),
+        },
+    );
+    assert_eq!(sent_claim(&res).claim.amount, Uint128::new(12500));
+    assert_eq!(
+        query_reward(deps.as_ref(), env_at(200), 1).amount,
+        Uint128::zero()
+    );
+
+    // an unstaking dragon keeps its earnings but leaves the budget to the others
+    execute_ok(
+        deps.as_mut(),
+        env_at(200),
+        "bob",
+        ExecuteMsg::StartUnstakingProcess {
+            token_id: Uint64::new(2),
+        },
+    );
+    assert_eq!(
+        query_reward(deps.as_ref(), env_at(300), 1).amount,
+        Uint128::new(10000)
+    );
+    assert_eq!(
+        query_reward(deps.as_ref(), env_at(300), 2).amount,
+        Uint128::new(7500)
+    );
+
+    let emissions: EmissionsResponse =
+        from_binary(&query(deps.as_ref(), env_at(300), QueryMsg::Emissions {}).unwrap()).unwrap();
+    assert_eq!(emissions.state.total_weight, Uint128::new(1));
+    assert_eq!(emissions.weights.len(), 1);
+}
+
+#[test]
+fn emission_weight_change_rebalances_staked_dragons() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    for (owner, kind) in [("alice", "common"), ("bob", "rare")] {
+        execute_ok(deps.as_mut(), mock_env(), MINTER, mint_msg(owner, kind));
+    }
+    let enable = ExecuteMsg::UpdateEmissionConfig {
+        config: EmissionConfig {
+            enabled: true,
+            tokens_per_second: Uint128::new(100),
+        },
+    };
+    execute_ok(deps.as_mut(), mock_env(), OWNER, enable);
+    for (owner, token_id) in [("alice", 1), ("bob", 2)] {
+        let msg = ExecuteMsg::StakeDragon {
+            token_id: Uint64::new(token_id),
+        };
+        execute_ok(deps.as_mut(), mock_env(), owner, msg);
+    }
+
+    execute_ok(
+        deps.as_mut(),
+        env_at(100),
+        OWNER,
+        ExecuteMsg::SetEmissionWeight {
+            kind: "rare".to_string(),
+            weight: Uint128::new(3),
+        },
+    );
+
+    // an even split for 100 seconds, then three quarters to the already staked rare
+    let alice = query_reward(deps.as_ref(), env_at(200), 1);
+    assert_eq!(alice.amount, Uint128::new(5000 + 2500));
+    let bob = query_reward(deps.as_ref(), env_at(200), 2);
+    assert_eq!(bob.emission_weight, Some(Uint128::new(3)));
+    assert_eq!(bob.amount, Uint128::new(5000 + 7500));
+    let emissions: EmissionsResponse =
+        from_binary(&query(deps.as_ref(), env_at(200), QueryMsg::Emissions {}).unwrap()).unwrap();
+    assert_eq!(emissions.state.total_weight, Uint128::new(4));
+
+    // leaving removes the new weight from the total
+    execute_ok(
+        deps.as_mut(),
+        env_at(200),
+        "bob",
+        ExecuteMsg::StartUnstakingProcess {
+            token_id: Uint64::new(2),
+        },
+    );
+    let emissions: EmissionsResponse =
+        from_binary(&query(deps.as_ref(), env_at(200), QueryMsg::Emissions {}).unwrap()).unwrap();
+    assert_eq!(emissions.state.total_weight, Uint128::new(1));
+}
+
+#[test]
+fn pause_blocks_groups_until_lifted_or_expired() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        OWNER,
+        ExecuteMsg::UpdateGuardian {
+            guardian: Some("guardian".to_string()),
+        },
+    );
+    let pause = ExecuteMsg::Pause {
+        groups: vec![PauseGroup::Stake, PauseGroup::Transfer],
+        reason: "exploit".to_string(),
+        expires: Some(Expiration::AtTime(env_at(100).block.time)),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        pause.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    execute_ok(deps.as_mut(), mock_env(), "guardian", pause);
+
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        MINTER,
+        mint_msg("alice", "common"),
+    );
+    let stake = ExecuteMsg::StakeDragon {
+        token_id: Uint64::new(1),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        stake.clone(),
+    )
+    .unwrap_err();
+    match err {
+        ContractError::Paused { group, reason } => {
+            assert_eq!(group, "stake");
+            assert_eq!(reason, "exploit");
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+    let status: PauseStatusResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PauseStatus {}).unwrap()).unwrap();
+    assert_eq!(status.guardian, Some("guardian".to_string()));
+    assert_eq!(status.paused.len(), 2);
+
+    // the pause lifts by itself once expired
+    execute_ok(deps.as_mut(), env_at(101), "alice", stake);
+
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        "guardian",
+        ExecuteMsg::Pause {
+            groups: vec![PauseGroup::Claim],
+            reason: "audit".to_string(),
+            expires: None,
+        },
+    );
+    let claim = ExecuteMsg::ClaimReward {
+        token_id: Uint64::new(1),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env_at(200),
+        mock_info("alice", &[]),
+        claim.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Paused { .. }));
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        OWNER,
+        ExecuteMsg::Unpause {
+            groups: vec![PauseGroup::Claim],
+        },
+    );
+    execute_ok(deps.as_mut(), env_at(200), "alice", claim);
+}
+
+#[test]
+fn planted_eggs_get_unique_birth_ids() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        OWNER,
+        ExecuteMsg::UpdateMinStakeTime {
+            time: Uint64::zero(),
+        },
+    );
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        MINTER,
+        mint_msg("alice", "common"),
+    );
+    let token_id = Uint64::new(1);
+    let plant = |deps: DepsMut, env: Env| -> String {
+        let res = execute_ok(deps, env, "alice", ExecuteMsg::PlantEgg { token_id });
+        let birth: DragonBirthWrapper = match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => from_binary(msg).unwrap(),
+            msg => panic!("unexpected message {:?}", msg),
+        };
+        birth.dragon_birth.id
+    };
+
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        "alice",
+        ExecuteMsg::StakeDragon { token_id },
+    );
+    let first = plant(deps.as_mut(), env_at(86400));
+    // restaking restarts the ovulation, so the dragon plants again
+    for msg in [
+        ExecuteMsg::StartUnstakingProcess { token_id },
+        ExecuteMsg::UnstakeDragon { token_id },
+        ExecuteMsg::StakeDragon { token_id },
+    ] {
+        execute_ok(deps.as_mut(), env_at(86400), "alice", msg);
+    }
+    let second = plant(deps.as_mut(), env_at(86400 * 2));
+    assert_eq!(first, "plant1");
+    assert_eq!(second, "plant2");
+}
+
+#[test]
+fn portfolio_summarises_owner_dragons() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    for (owner, kind) in [
+        ("alice", "common"),
+        ("alice", "common"),
+        ("alice", "rare"),
+        ("bob", "common"),
+    ] {
+        execute_ok(deps.as_mut(), mock_env(), MINTER, mint_msg(owner, kind));
+    }
+    for token_id in [1, 3] {
+        let msg = ExecuteMsg::StakeDragon {
+            token_id: Uint64::new(token_id),
+        };
+        execute_ok(deps.as_mut(), mock_env(), "alice", msg);
+    }
+    execute_ok(
+        deps.as_mut(),
+        env_at(86400),
+        "alice",
+        ExecuteMsg::StartUnstakingProcess {
+            token_id: Uint64::new(3),
+        },
+    );
+
+    let msg = QueryMsg::Portfolio {
+        owner: "alice".to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let portfolio: PortfolioResponse =
+        from_binary(&query(deps.as_ref(), env_at(86400 * 2), msg).unwrap()).unwrap();
+    assert_eq!(
+        portfolio.page_kinds,
+        vec![
+            KindCount {
+                kind: "common".to_string(),
+                staked: 1,
+                unstaked: 1,
+            },
+            KindCount {
+                kind: "rare".to_string(),
+                staked: 1,
+                unstaked: 0,
+            },
+        ]
+    );
+    // two days of common plus the single day the rare earned before unstaking
+    assert_eq!(
+        portfolio.page_pending_reward,
+        Uint128::new(1000000 + 3000000)
+    );
+    assert_eq!(portfolio.unstaking.len(), 1);
+    assert_eq!(portfolio.unstaking[0].token_id, "3");
+    assert_eq!(
+        portfolio.unstaking[0].withdrawable_at.u64(),
+        env_at(86400 + 1209600).block.time.seconds()
+    );
+    assert_eq!(portfolio.ready_to_plant, vec!["1"]);
+    assert_eq!(portfolio.next_start_after, None);
+}
+
+#[test]
+fn portfolio_pages_and_skips_unavailable_rewards() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    for kind in ["common", "common", "rare"] {
+        execute_ok(deps.as_mut(), mock_env(), MINTER, mint_msg("alice", kind));
+    }
+    for token_id in [1, 3] {
+        let msg = ExecuteMsg::StakeDragon {
+            token_id: Uint64::new(token_id),
+        };
+        execute_ok(deps.as_mut(), mock_env(), "alice", msg);
+    }
+    // the contract refuses to remove a schedule that is in use, so drop it from
+    // storage to exercise the fallback
+    REWARD_SCHEDULES.remove(deps.as_mut().storage, "rare");
+
+    let portfolio = |start_after: Option<u64>| -> PortfolioResponse {
+        let msg = QueryMsg::Portfolio {
+            owner: "alice".to_string(),
+            start_after,
+            limit: Some(2),
+        };
+        from_binary(&query(deps.as_ref(), env_at(86400), msg).unwrap()).unwrap()
+    };
+    let first = portfolio(None);
+    assert_eq!(first.page_kinds.len(), 1);
+    assert_eq!(first.page_kinds[0].staked + first.page_kinds[0].unstaked, 2);
+    assert_eq!(first.page_pending_reward, Uint128::new(500000));
+    assert_eq!(first.next_start_after, Some(2));
+
+    let second = portfolio(first.next_start_after);
+    assert_eq!(second.page_kinds[0].kind, "rare");
+    assert_eq!(second.page_pending_reward, Uint128::zero());
+    assert_eq!(second.reward_unavailable, vec!["3"]);
+    assert_eq!(second.next_start_after, None);
+}
+
+fn dragon_ids(deps: Deps, msg: QueryMsg) -> Vec<String> {
+    let res: DragonListResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+    res.dragons.into_iter().map(|d| d.token_id).collect()
+}
+
+#[test]
+fn indexed_dragon_queries() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    for (owner, kind) in [
+        ("alice", "common"),
+        ("bob", "rare"),
+        ("alice", "rare"),
+        ("alice", "common"),
+    ] {
+        execute_ok(deps.as_mut(), mock_env(), MINTER, mint_msg(owner, kind));
+    }
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        "alice",
+        ExecuteMsg::StakeDragon {
+            token_id: Uint64::new(3),
+        },
+    );
+
+    let user_dragons = |start_after, limit, is_staked| QueryMsg::RangeUserDragons {
+        start_after,
+        limit,
+        owner: "alice".to_string(),
+        is_staked,
+    };
+    assert_eq!(
+        dragon_ids(deps.as_ref(), user_dragons(None, None, None)),
+        vec!["1", "3", "4"]
+    );
+    assert_eq!(
+        dragon_ids(deps.as_ref(), user_dragons(Some(1), Some(1), None)),
+        vec!["3"]
+    );
+    assert_eq!(
+        dragon_ids(deps.as_ref(), user_dragons(None, None, Some(false))),
+        vec!["1", "4"]
+    );
+    assert_eq!(
+        dragon_ids(
+            deps.as_ref(),
+            QueryMsg::QueryUserDragon {
+                owner: "bob".to_string()
+            }
+        ),
+        vec!["2"]
+    );
+    assert_eq!(
+        dragon_ids(
+            deps.as_ref(),
+            QueryMsg::RangeKindDragons {
+                start_after: None,
+                limit: None,
+                kind: "rare".to_string()
+            }
+        ),
+        vec!["2", "3"]
+    );
+    assert_eq!(
+        dragon_ids(
+            deps.as_ref(),
+            QueryMsg::RangeStakedDragons {
+                start_after: None,
+                limit: None,
+                is_staked: true
+            }
+        ),
+        vec!["3"]
+    );
+}
+
+#[test]
+fn migrate_from_v0_1_rebuilds_dragon_indexes() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    set_contract_version(deps.as_mut().storage, "crates.io:cw721-base", "0.13.2").unwrap();
+
+    // dragons written by the previous, non indexed, storage layout
+    let legacy_dragons: Map<u64, Dragon> = Map::new("dragons_list");
+    for (id, owner) in [(1u64, "alice"), (2, "bob"), (3, "alice")] {
+        let dragon = Dragon {
+            owner: owner.to_string(),
+            token_id: id.to_string(),
+            kind: "common".to_string(),
+            ovulation_period: 1,
+            hatch: Uint64::zero(),
+            daily_income: "1".to_string(),
+            is_staked: false,
+            stake_start_time: Uint64::zero(),
+            reward_start_time: Uint64::zero(),
+            unstaking_start_time: Uint64::zero(),
+            unstaking_process: false,
+            reward_end_time: Uint64::zero(),
+            xp: Uint64::zero(),
+        };
+        legacy_dragons
+            .save(deps.as_mut().storage, id, &dragon)
+            .unwrap();
+    }
+    let alice_dragons = QueryMsg::QueryUserDragon {
+        owner: "alice".to_string(),
+    };
+    assert!(dragon_ids(deps.as_ref(), alice_dragons.clone()).is_empty());
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+    assert_eq!(dragon_ids(deps.as_ref(), alice_dragons), vec!["1", "3"]);
+    let version = get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn migrate_from_v0_1_sets_reward_schedules_for_claims() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        MINTER,
+        mint_msg("alice", "common"),
+    );
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        "alice",
+        ExecuteMsg::StakeDragon {
+            token_id: Uint64::new(1),
+        },
+    );
+    // v0.1 paid fixed rates and stored no schedules
+    for kind in ["common", "rare"] {
+        REWARD_SCHEDULES.remove(deps.as_mut().storage, kind);
+    }
+    set_contract_version(deps.as_mut().storage, "crates.io:cw721-base", "0.13.2").unwrap();
+
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err();
+    assert!(matches!(err, ContractError::MissingRewardSchedule { kind } if kind == "common"));
+
+    let msg = MigrateMsg {
+        reward_schedules: vec![KindRewardSchedule {
+            kind: "common".to_string(),
+            schedule: schedule(500000, 0, None),
+        }],
+    };
+    migrate(deps.as_mut(), mock_env(), msg).unwrap();
+    let res = execute_ok(
+        deps.as_mut(),
+        env_at(86400),
+        "alice",
+        ExecuteMsg::ClaimReward {
+            token_id: Uint64::new(1),
+        },
+    );
+    assert_eq!(claimed_amount(&res), Uint128::new(500000));
+}
+
+#[test]
+fn staked_dragons_are_queryable_at_past_heights() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    for owner in ["alice", "bob", "alice"] {
+        execute_ok(deps.as_mut(), mock_env(), MINTER, mint_msg(owner, "common"));
+    }
+    let start = mock_env().block.height;
+    let at_height = |blocks: u64, seconds: u64| {
+        let mut env = env_at(seconds);
+        env.block.height = start + blocks;
+        env
+    };
+    let stake = |token_id: u64| ExecuteMsg::StakeDragon {
+        token_id: Uint64::new(token_id),
+    };
+    execute_ok(deps.as_mut(), at_height(0, 0), "alice", stake(1));
+    execute_ok(deps.as_mut(), at_height(1, 5), "bob", stake(2));
+    execute_ok(deps.as_mut(), at_height(1, 5), "alice", stake(3));
+    execute_ok(
+        deps.as_mut(),
+        at_height(2, 10),
+        "alice",
+        ExecuteMsg::StartUnstakingProcess {
+            token_id: Uint64::new(1),
+        },
+    );
+    execute_ok(
+        deps.as_mut(),
+        at_height(3, 10 + 1209600),
+        "alice",
+        ExecuteMsg::UnstakeDragon {
+            token_id: Uint64::new(1),
+        },
+    );
+
+    let staked_at = |deps: Deps, owner: &str, blocks: u64| -> Vec<Uint64> {
+        let msg = QueryMsg::StakedAt {
+            owner: owner.to_string(),
+            height: start + blocks,
+        };
+        let res: StakedAtResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+        res.token_ids
+    };
+    let total_at = |deps: Deps, blocks: u64| -> u64 {
+        let msg = QueryMsg::TotalStakedAt {
+            height: start + blocks,
+        };
+        let res: TotalStakedAtResponse =
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+        res.total
+    };
+    // a height reflects the state before the transactions of that block
+    assert!(staked_at(deps.as_ref(), "alice", 0).is_empty());
+    assert_eq!(staked_at(deps.as_ref(), "alice", 1), vec![Uint64::new(1)]);
+    assert_eq!(
+        staked_at(deps.as_ref(), "alice", 3),
+        vec![Uint64::new(1), Uint64::new(3)]
+    );
+    assert_eq!(staked_at(deps.as_ref(), "alice", 4), vec![Uint64::new(3)]);
+    assert_eq!(staked_at(deps.as_ref(), "bob", 2), vec![Uint64::new(2)]);
+    assert_eq!(
+        [0, 1, 2, 3, 4].map(|blocks| total_at(deps.as_ref(), blocks)),
+        [0, 1, 3, 3, 2]
+    );
+
+    // v0.2 staked dragons without snapshots, they are recorded when migrating
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    execute_ok(
+        deps.as_mut(),
+        mock_env(),
+        MINTER,
+        mint_msg("alice", "common"),
+    );
+    let mut dragon = dragon_info().load(deps.as_ref().storage, 1).unwrap();
+    dragon.is_staked = true;
+    dragon_info()
+        .save(deps.as_mut().storage, 1, &dragon)
+        .unwrap();
+    set_contract_version(deps.as_mut().storage, "crates.io:dragon-mint", "0.2.0").unwrap();
+    migrate(deps.as_mut(), at_height(10, 0), MigrateMsg::default()).unwrap();
+    assert_eq!(staked_at(deps.as_ref(), "alice", 11), vec![Uint64::new(1)]);
+    assert_eq!(total_at(deps.as_ref(), 11), 1);
+}
+
+#[test]
+fn voting_power_follows_staked_dragons_and_kind_weights() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    for (owner, kind) in [("alice", "common"), ("alice", "rare"), ("bob", "rare")] {
+        execute_ok(deps.as_mut(), mock_env(), MINTER, mint_msg(owner, kind));
+    }
+    let start = mock_env().block.height;
+    let at_height = |blocks: u64| {
+        let mut env = mock_env();
+        env.block.height = start + blocks;
+        env
+    };
+    let set_rare_weight = |weight: u128| ExecuteMsg::SetVotingWeight {
+        kind: "rare".to_string(),
+        weight: Uint128::new(weight),
+    };
+    let err = execute(
+        deps.as_mut(),
+        at_height(0),
+        mock_info("alice", &[]),
+        set_rare_weight(3),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    execute_ok(deps.as_mut(), at_height(0), OWNER, set_rare_weight(3));
+    for (owner, id) in [("alice", 1), ("alice", 2), ("bob", 3)] {
+        execute_ok(
+            deps.as_mut(),
+            at_height(1),
+            owner,
+            ExecuteMsg::StakeDragon {
+                token_id: Uint64::new(id),
+            },
+        );
+    }
+    execute_ok(deps.as_mut(), at_height(3), OWNER, set_rare_weight(5));
+
+    let power = |deps: Deps, address: &str, blocks: Option<u64>| -> Uint128 {
+        let msg = QueryMsg::VotingPowerAtHeight {
+            address: address.to_string(),
+            height: blocks.map(|blocks| start + blocks),
+        };
+        let res: VotingPowerAtHeightResponse =
+            from_binary(&query(deps, at_height(10), msg).unwrap()).unwrap();
+        res.power
+    };
+    let total = |deps: Deps, blocks: u64| -> Uint128 {
+        let msg = QueryMsg::TotalPowerAtHeight {
+            height: Some(start + blocks),
+        };
+        let res: TotalPowerAtHeightResponse =
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+        res.power
+    };
+    assert_eq!(power(deps.as_ref(), "alice", Some(1)), Uint128::zero());
+    assert_eq!(power(deps.as_ref(), "alice", Some(2)), Uint128::new(4));
+    assert_eq!(power(deps.as_ref(), "bob", Some(2)), Uint128::new(3));
+    assert_eq!(total(deps.as_ref(), 1), Uint128::zero());
+    assert_eq!(total(deps.as_ref(), 3), Uint128::new(7));
+    // the new weight only counts from the block after it was set
+    assert_eq!(total(deps.as_ref(), 4), Uint128::new(11));
+    assert_eq!(power(deps.as_ref(), "alice", None), Uint128::new(6));
+
+    let weights: VotingWeightsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::VotingWeights {}).unwrap())
+            .unwrap();
+    assert_eq!(weights.weights.len(), 2);
+    assert!(weights
+        .weights
+        .iter()
+        .any(|w| w.kind == "rare" && w.weight == Uint128::new(5)));
+}
+
+#[test]
+fn migrate_rejects_other_contracts_and_downgrades() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+
+    set_contract_version(deps.as_mut().storage, "crates.io:egg-mint", "0.1.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Migrate(MigrateError::WrongContract { .. })
+    ));
+
+    set_contract_version(deps.as_mut().storage, "crates.io:dragon-mint", "99.0.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Migrate(MigrateError::CannotDowngrade { .. })
+    ));
+
+    // any other cw721, such as egg-mint v0.1, has no dragon-mint state to take over
+    let mut deps = mock_dependencies();
+    set_contract_version(deps.as_mut().storage, "crates.io:cw721-base", "0.13.2").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Migrate(MigrateError::WrongContract { .. })
+    ));
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum ReceiverExecuteMsg {
+    ReceiveNft(Cw721ReceiveMsg),
+}
+
+fn contract_dragon_mint() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(execute, instantiate, query)
+            .with_migrate(migrate)
+            .with_reply(reply),
+    )
+}
+
+/// Reward contract stand-in that pays every claim, or fails them all when `underfunded`.
+fn contract_reward(underfunded: bool) -> Box<dyn Contract<Empty>> {
+    let instantiate = |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+        Ok(Response::new())
+    };
+    let query = |_: Deps, _: Env, _: Empty| -> StdResult<Binary> { to_binary(&Empty {}) };
+    if underfunded {
+        Box::new(ContractWrapper::new(
+            |_: DepsMut, _: Env, _: MessageInfo, _: ClaimMessage| -> StdResult<Response> {
+                Err(StdError::generic_err("insufficient reward balance"))
+            },
+            instantiate,
+            query,
+        ))
+    } else {
+        Box::new(ContractWrapper::new(
+            |_: DepsMut, _: Env, _: MessageInfo, _: ClaimMessage| -> StdResult<Response> {
+                Ok(Response::new())
+            },
+            instantiate,
+            query,
+        ))
+    }
+}
+
+fn use_reward_contract(app: &mut App, dragons: &Addr, underfunded: bool) {
+    let code_id = app.store_code(contract_reward(underfunded));
+    let reward = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(OWNER),
+            &Empty {},
+            &[],
+            "reward",
+            None,
+        )
+        .unwrap();
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        dragons.clone(),
+        &ExecuteMsg::UpdateRewardContractAddress {
+            new_address: reward.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn query_owed(app: &App, dragons: &Addr, address: &str) -> Uint128 {
+    let res: OwedResponse = app
+        .wrap()
+        .query_wasm_smart(
+            dragons,
+            &QueryMsg::Owed {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+    res.amount
+}
+
+fn contract_nft_receiver() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        |_: DepsMut, _: Env, _: MessageInfo, _: ReceiverExecuteMsg| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |_: Deps, _: Env, _: Empty| -> StdResult<Binary> { to_binary(&Empty {}) },
+    ))
+}
+
+/// Instantiates dragon-mint in a multi-test app and mints dragon 1 and 2 to alice.
+fn setup_app() -> (App, Addr) {
+    let mut app = App::default();
+    let code_id = app.store_code(contract_dragon_mint());
+    let msg = InstantiateMsg {
+        base: Cw721InstantiateMsg {
+            name: "STAKE DRAGONS".to_string(),
+            symbol: "DRGN".to_string(),
+            minter: MINTER.to_string(),
+        },
+        size: Uint64::new(100),
+        base_price: Uint64::new(1),
+        reward_contract_address: "reward".to_string(),
+        reward_schedules: vec![KindRewardSchedule {
+            kind: "common".to_string(),
+            schedule: schedule(500000, 0, None),
+        }],
+        breeding_fee: None,
+    };
+    let dragons = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(OWNER),
+            &msg,
+            &[],
+            "dragon-mint",
+            None,
+        )
+        .unwrap();
+    for _ in 0..2 {
+        app.execute_contract(
+            Addr::unchecked(MINTER),
+            dragons.clone(),
+            &mint_msg("alice", "common"),
+            &[],
+        )
+        .unwrap();
+    }
+    (app, dragons)
+}
+
+fn assert_dragon_owner(app: &App, dragons: &Addr, token_id: u64, owner: &str) {
+    let dragon: DragonResponse = app
+        .wrap()
+        .query_wasm_smart(
+            dragons,
+            &QueryMsg::DragonInfo {
+                id: Uint64::new(token_id),
+            },
+        )
+        .unwrap();
+    assert_eq!(dragon.owner, owner);
+    let nft_owner: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            dragons,
+            &QueryMsg::OwnerOf {
+                token_id: token_id.to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(nft_owner.owner, owner);
+}
+
+fn stake(app: &mut App, dragons: &Addr, sender: &str, token_id: u64) -> anyhow::Result<()> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        dragons.clone(),
+        &ExecuteMsg::StakeDragon {
+            token_id: Uint64::new(token_id),
+        },
+        &[],
+    )
+    .map(|_| ())
+}
+
+#[test]
+fn failed_reward_payout_is_owed_until_withdrawn() {
+    let (mut app, dragons) = setup_app();
+    use_reward_contract(&mut app, &dragons, true);
+    stake(&mut app, &dragons, "alice", 1).unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(86400));
+
+    // the claim goes through although the reward contract could not pay it
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        dragons.clone(),
+        &ExecuteMsg::ClaimReward {
+            token_id: Uint64::new(1),
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(query_owed(&app, &dragons, "alice"), Uint128::new(500000));
+
+    let withdraw = ExecuteMsg::WithdrawOwed {};
+    app.execute_contract(Addr::unchecked("alice"), dragons.clone(), &withdraw, &[])
+        .unwrap();
+    assert_eq!(query_owed(&app, &dragons, "alice"), Uint128::new(500000));
+
+    use_reward_contract(&mut app, &dragons, false);
+    app.execute_contract(Addr::unchecked("alice"), dragons.clone(), &withdraw, &[])
+        .unwrap();
+    assert_eq!(query_owed(&app, &dragons, "alice"), Uint128::zero());
+    let err = app
+        .execute_contract(Addr::unchecked("alice"), dragons.clone(), &withdraw, &[])
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingOwed { .. }
+    ));
+}
+
+#[test]
+fn emergency_unstake_sends_penalty_to_treasury() {
+    let (mut app, dragons) = setup_app();
+    use_reward_contract(&mut app, &dragons, true);
+    stake(&mut app, &dragons, "alice", 1).unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(86400));
+    let emergency_unstake = ExecuteMsg::EmergencyUnstake {
+        token_id: Uint64::new(1),
+    };
+
+    let err = app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            dragons.clone(),
+            &emergency_unstake,
+            &[],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::EmergencyUnstakeDisabled {}
+    ));
+    let owner = Addr::unchecked(OWNER);
+    let set_penalty = |penalty: &str| ExecuteMsg::UpdateEmergencyUnstakePenalty {
+        penalty: Some(Decimal::from_str(penalty).unwrap()),
+    };
+    let err = app
+        .execute_contract(owner.clone(), dragons.clone(), &set_penalty("1.5"), &[])
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidEmergencyUnstakePenalty {}
+    ));
+    app.execute_contract(owner.clone(), dragons.clone(), &set_penalty("0.25"), &[])
+        .unwrap();
+    app.execute_contract(
+        owner,
+        dragons.clone(),
+        &ExecuteMsg::UpdateTreasury {
+            treasury: "treasury".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // both payouts failed, so each recipient keeps its share as owed
+    let res = app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            dragons.clone(),
+            &emergency_unstake,
+            &[],
+        )
+        .unwrap();
+    let penalty = res
+        .events
+        .iter()
+        .find(|event| event.ty == "wasm-emergency_unstake_penalty")
+        .unwrap();
+    assert!(penalty
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "amount" && attr.value == "125000"));
+    assert_eq!(query_owed(&app, &dragons, "alice"), Uint128::new(375000));
+    assert_eq!(query_owed(&app, &dragons, "treasury"), Uint128::new(125000));
+    let dragon: DragonResponse = app
+        .wrap()
+        .query_wasm_smart(&dragons, &QueryMsg::DragonInfo { id: Uint64::new(1) })
+        .unwrap();
+    assert!(!dragon.is_staked);
+}
+
+#[test]
+fn approved_spender_transfer_moves_dragon_record() {
+    let (mut app, dragons) = setup_app();
+    let alice = Addr::unchecked("alice");
+    app.execute_contract(
+        alice.clone(),
+        dragons.clone(),
+        &ExecuteMsg::Approve {
+            spender: "spender".to_string(),
+            token_id: "1".to_string(),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // a random address is still rejected by cw721-base
+    let transfer = ExecuteMsg::TransferNft {
+        recipient: "bob".to_string(),
+        token_id: Uint64::new(1),
+    };
+    let err = app
+        .execute_contract(Addr::unchecked("random"), dragons.clone(), &transfer, &[])
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Cw721(Cw721ContractError::Unauthorized {})
+    ));
+    assert_dragon_owner(&app, &dragons, 1, "alice");
+
+    app.execute_contract(Addr::unchecked("spender"), dragons.clone(), &transfer, &[])
+        .unwrap();
+    assert_dragon_owner(&app, &dragons, 1, "bob");
+
+    // the previous owner lost every right on the dragon
+    stake(&mut app, &dragons, "alice", 1).unwrap_err();
+    stake(&mut app, &dragons, "bob", 1).unwrap();
+}
+
+#[test]
+fn operator_transfer_moves_dragon_record_unless_staked() {
+    let (mut app, dragons) = setup_app();
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        dragons.clone(),
+        &ExecuteMsg::ApproveAll {
+            operator: "market".to_string(),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+    stake(&mut app, &dragons, "alice", 2).unwrap();
+
+    let transfer = |token_id| ExecuteMsg::TransferNft {
+        recipient: "bob".to_string(),
+        token_id: Uint64::new(token_id),
+    };
+    let err = app
+        .execute_contract(
+            Addr::unchecked("market"),
+            dragons.clone(),
+            &transfer(2),
+            &[],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::StakedDragonCantBeTransferred {}
+    ));
+    assert_dragon_owner(&app, &dragons, 2, "alice");
+
+    app.execute_contract(
+        Addr::unchecked("market"),
+        dragons.clone(),
+        &transfer(1),
+        &[],
+    )
+    .unwrap();
+    assert_dragon_owner(&app, &dragons, 1, "bob");
+}
+
+#[test]
+fn send_nft_moves_dragon_record_to_contract() {
+    let (mut app, dragons) = setup_app();
+    let receiver_id = app.store_code(contract_nft_receiver());
+    let receiver = app
+        .instantiate_contract(
+            receiver_id,
+            Addr::unchecked(OWNER),
+            &Empty {},
+            &[],
+            "receiver",
+            None,
+        )
+        .unwrap();
+    let send = ExecuteMsg::SendNft {
+        contract: receiver.to_string(),
+        token_id: "1".to_string(),
+        msg: Binary::default(),
+    };
+
+    stake(&mut app, &dragons, "alice", 1).unwrap();
+    let err = app
+        .execute_contract(Addr::unchecked("alice"), dragons.clone(), &send, &[])
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::StakedDragonCantBeTransferred {}
+    ));
+
+    let send = ExecuteMsg::SendNft {
+        contract: receiver.to_string(),
+        token_id: "2".to_string(),
+        msg: Binary::default(),
+    };
+    app.execute_contract(Addr::unchecked("alice"), dragons.clone(), &send, &[])
+        .unwrap();
+    assert_dragon_owner(&app, &dragons, 2, receiver.as_str());
+    stake(&mut app, &dragons, "alice", 2).unwrap_err();
+}
+
+#[test]
+fn burn_removes_dragon_record() {
+    let (mut app, dragons) = setup_app();
+    stake(&mut app, &dragons, "alice", 1).unwrap();
+    let burn = |token_id: &str| ExecuteMsg::Burn {
+        token_id: token_id.to_string(),
+    };
+
+    let err = app
+        .execute_contract(Addr::unchecked("alice"), dragons.clone(), &burn("1"), &[])
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::StakedDragonCantBeBurned {}
+    ));
+    app.execute_contract(Addr::unchecked("bob"), dragons.clone(), &burn("2"), &[])
+        .unwrap_err();
+
+    app.execute_contract(Addr::unchecked("alice"), dragons.clone(), &burn("2"), &[])
+        .unwrap();
+    let res: StdResult<DragonResponse> = app
+        .wrap()
+        .query_wasm_smart(&dragons, &QueryMsg::DragonInfo { id: Uint64::new(2) });
+    res.unwrap_err();
+    let res: DragonListResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &dragons,
+            &QueryMsg::QueryUserDragon {
+                owner: "alice".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.dragons.len(), 1);
+}
+
+proptest! {
+    #[test]
+    fn calculate_reward_matches_claimed_amount(
+        rate in 0u128..1_000_000_000_000u128,
+        precision in 0u32..=18,
+        cap in proptest::option::of(0u128..1_000_000_000_000u128),
+        first_claim in 0u64..10_000_000,
+        unstake_after in 0u64..10_000_000,
+        second_claim in 0u64..10_000_000,
+    ) {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut());
+        execute_ok(deps.as_mut(), mock_env(), OWNER, ExecuteMsg::SetRewardSchedule {
+            kind: "rare".to_string(),
+            schedule: schedule(rate, precision, cap),
+        });
+        execute_ok(deps.as_mut(), mock_env(), MINTER, mint_msg("alice", "rare"));
+        execute_ok(deps.as_mut(), mock_env(), "alice", ExecuteMsg::StakeDragon {
+            token_id: Uint64::new(1),
+        });
+
+        // claim while staked
+        let now = first_claim;
+        let expected = query_reward(deps.as_ref(), env_at(now), 1);
+        let res = execute_ok(deps.as_mut(), env_at(now), "alice", ExecuteMsg::ClaimReward {
+            token_id: Uint64::new(1),
+        });
+        prop_assert_eq!(claimed_amount(&res), expected.amount);
+
+        // claim after the unstaking process froze the reward
+        let now = now + unstake_after;
+        execute_ok(deps.as_mut(), env_at(now), "alice", ExecuteMsg::StartUnstakingProcess {
+            token_id: Uint64::new(1),
+        });
+        let now = now + second_claim;
+        let expected = query_reward(deps.as_ref(), env_at(now), 1);
+        prop_assert_eq!(expected.elapsed_seconds, Uint64::new(unstake_after));
+        let res = execute_ok(deps.as_mut(), env_at(now), "alice", ExecuteMsg::ClaimReward {
+            token_id: Uint64::new(1),
+        });
+        prop_assert_eq!(claimed_amount(&res), expected.amount);
+        prop_assert_eq!(query_reward(deps.as_ref(), env_at(now), 1).amount, Uint128::zero());
+    }
+}
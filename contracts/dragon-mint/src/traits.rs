@@ -0,0 +1,116 @@
+use crate::msg::Trait;
+use crate::ContractError;
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use stake_dragons_utils::traits::{is_valid_kind, is_valid_ovulation_period};
+use std::str::FromStr;
+
+/// Kind of a dragon. Kinds are open: any well-formed kind dragon-mint has a reward schedule
+/// for can be minted, so adding one only takes a `SetRewardSchedule`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Kind(String);
+
+impl Kind {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Kind {
+    type Err = ContractError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if !is_valid_kind(value) {
+            return Err(invalid_value("kind", value));
+        }
+        Ok(Kind(value.to_string()))
+    }
+}
+
+/// Traits a dragon is minted with, parsed from the `Trait` list of the mint message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DragonTraits {
+    pub kind: Kind,
+    /// Days between two eggs.
+    pub ovulation_period: u64,
+    pub daily_income: Uint128,
+}
+
+impl DragonTraits {
+    /// Every trait must be given exactly once and no other trait is accepted.
+    pub fn from_traits(traits: &[Trait]) -> Result<Self, ContractError> {
+        let mut kind = None;
+        let mut ovulation_period = None;
+        let mut daily_income = None;
+        for item in traits {
+            let value = item.value.as_str();
+            let duplicated = match item.trait_type.as_str() {
+                "kind" => kind.replace(Kind::from_str(value)?).is_some(),
+                "ovulation_period" => ovulation_period
+                    .replace(parse_ovulation_period(value)?)
+                    .is_some(),
+                "daily_income" => daily_income.replace(parse_daily_income(value)?).is_some(),
+                _ => {
+                    return Err(ContractError::UnexpectedTraitType {
+                        trait_type: item.trait_type.clone(),
+                    })
+                }
+            };
+            if duplicated {
+                return Err(ContractError::DuplicateTrait {
+                    trait_type: item.trait_type.clone(),
+                });
+            }
+        }
+        Ok(DragonTraits {
+            kind: kind.ok_or_else(|| missing("kind"))?,
+            ovulation_period: ovulation_period.ok_or_else(|| missing("ovulation_period"))?,
+            daily_income: daily_income.ok_or_else(|| missing("daily_income"))?,
+        })
+    }
+
+    /// The traits as cw721 metadata attributes.
+    pub fn to_attributes(&self) -> Vec<Trait> {
+        vec![
+            attribute("kind", self.kind.as_str().to_string()),
+            attribute("ovulation_period", self.ovulation_period.to_string()),
+            attribute("daily_income", self.daily_income.to_string()),
+        ]
+    }
+}
+
+fn parse_ovulation_period(value: &str) -> Result<u64, ContractError> {
+    match value.parse::<u64>() {
+        Ok(days) if is_valid_ovulation_period(days) => Ok(days),
+        _ => Err(invalid_value("ovulation_period", value)),
+    }
+}
+
+fn parse_daily_income(value: &str) -> Result<Uint128, ContractError> {
+    match value.parse::<u128>() {
+        Ok(income) if income > 0 => Ok(Uint128::new(income)),
+        _ => Err(invalid_value("daily_income", value)),
+    }
+}
+
+fn attribute(trait_type: &str, value: String) -> Trait {
+    Trait {
+        display_type: None,
+        trait_type: trait_type.to_string(),
+        value,
+    }
+}
+
+fn invalid_value(trait_type: &str, value: &str) -> ContractError {
+    ContractError::InvalidTraitValue {
+        trait_type: trait_type.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn missing(trait_type: &str) -> ContractError {
+    ContractError::MissingTrait {
+        trait_type: trait_type.to_string(),
+    }
+}
@@ -1,25 +1,41 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
-    Uint64,
+    coins, from_slice, to_binary, Addr, BankMsg, Binary, Deps, DepsMut, Empty, Env, Event,
+    MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResult,
+    Uint128, Uint64, WasmMsg,
 };
-use cw2::set_contract_version;
-use cw_utils::Expiration;
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_utils::{must_pay, nonpayable, Expiration};
+use stake_dragons_utils::migrate::stored_version;
 use std::ops::Add;
 
+use crate::drand::DrandConfig;
 use crate::error::ContractError;
+use crate::hatch::{beacon_roll, reveal_roll, HatchCommit, HatchConfig, Rarity};
 use crate::msg::{
-    CollectionInfoResponse, CustomMintMsg, ExecuteMsg, Extension, InstantiateMsg,
-    OwnedEggInfoResponse, QueryMsg,
+    CollectionInfoResponse, CustomMintMsg, DragonContractResponse, DragonExecuteMsg, DragonMintMsg,
+    DragonParent, DragonQueryMsg, DragonRewardSchedulesResponse, ExecuteMsg, Extension,
+    IncubationResponse, InstantiateMsg, Metadata, MigrateMsg, OwnedEggInfoResponse, QueryMsg,
+    ReceiveMsg, Trait,
+};
+use crate::sale::{SaleConfig, SalePayment};
+use crate::state::{
+    CollectionInfo, PendingHatch, BIRTH_IDS, COLLECTION_INFO, DRAGON_CONTRACT, DRAND_BEACONS,
+    DRAND_CONFIG, DRAND_HATCHES, DRAND_HATCH_ROUNDS, HATCH_COMMITS, HATCH_CONFIG, HATCH_NONCE,
+    HATCH_TIMES, OWNED_EGG_COUNT, PARENT_KINDS, PENDING_HATCHES, PURCHASES, SALE_CONFIG,
+    SALE_TOKEN_SEQ,
 };
-use crate::state::{CollectionInfo, COLLECTION_INFO, OWNED_EGG_COUNT};
 pub type Cw721Contract<'a> = cw721_base::Cw721Contract<'a, Extension, Empty>;
 pub type Cw721ExecuteMsg = cw721_base::ExecuteMsg<Extension>;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:egg-mint";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const LEGACY_CONTRACT_NAME: &str = "crates.io:cw721-base";
+/// Eggs hatched by one drand beacon submission
+const MAX_DRAND_HATCHES: usize = 30;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -28,8 +44,6 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-
     let minter = deps.api.addr_validate(&msg.base.minter)?;
     let res = Cw721Contract::default().instantiate(deps.branch(), env, info, msg.base.clone());
 
@@ -38,6 +52,8 @@ pub fn instantiate(
             method: "instantiate".to_string(),
         });
     }
+    // cw721-base records its own version, so ours has to be written afterwards
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     let collection_info = CollectionInfo {
         name: msg.base.name,
         symbol: msg.base.symbol,
@@ -48,9 +64,39 @@ pub fn instantiate(
     };
     COLLECTION_INFO.save(deps.storage, &collection_info)?;
     OWNED_EGG_COUNT.save(deps.storage, &Uint64::zero())?;
+    if let Some(dragon_contract) = msg.dragon_contract {
+        DRAGON_CONTRACT.save(deps.storage, &deps.api.addr_validate(&dragon_contract)?)?;
+    }
+    if let Some(config) = msg.hatch_config {
+        config.validate()?;
+        validate_hatch_kinds(deps.as_ref(), &config)?;
+        HATCH_CONFIG.save(deps.storage, &config)?;
+    }
     Ok(Response::new().add_attribute("method", "instantiate"))
 }
 
+/// Checks dragon-mint has a reward schedule, and so mints, every kind the rarities hatch.
+/// Skipped until the dragon contract is set.
+fn validate_hatch_kinds(deps: Deps, config: &HatchConfig) -> Result<(), ContractError> {
+    let dragon_contract = match DRAGON_CONTRACT.may_load(deps.storage)? {
+        Some(dragon_contract) => dragon_contract,
+        None => return Ok(()),
+    };
+    let res: DragonRewardSchedulesResponse = deps
+        .querier
+        .query_wasm_smart(dragon_contract, &DragonQueryMsg::RewardSchedules {})?;
+    match config
+        .rarities
+        .iter()
+        .find(|rarity| !res.schedules.iter().any(|s| s.kind == rarity.kind))
+    {
+        Some(rarity) => Err(ContractError::InvalidHatchConfig {
+            reason: format!("dragon contract cannot mint kind {}", rarity.kind),
+        }),
+        None => Ok(()),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -82,6 +128,30 @@ pub fn execute(
             execute_approve_all(deps, env, info, operator, expires)
         }
         ExecuteMsg::RevokeAll { operator } => execute_revoke_all(deps, env, info, operator),
+        ExecuteMsg::UpdateDragonContract { address } => {
+            execute_update_dragon_contract(deps, info, address)
+        }
+        ExecuteMsg::DragonBirth { id, owner, parents } => {
+            execute_dragon_birth(deps, env, info, id, owner, parents)
+        }
+        ExecuteMsg::UpdateHatchConfig { config } => execute_update_hatch_config(deps, info, config),
+        ExecuteMsg::CommitHatch {
+            token_id,
+            commitment,
+        } => execute_commit_hatch(deps, env, info, token_id, commitment),
+        ExecuteMsg::Hatch { token_id, secret } => execute_hatch(deps, env, info, token_id, secret),
+        ExecuteMsg::UpdateDrandConfig { config } => execute_update_drand_config(deps, info, config),
+        ExecuteMsg::CommitDrandHatch { token_id } => {
+            execute_commit_drand_hatch(deps, env, info, token_id)
+        }
+        ExecuteMsg::SubmitDrandBeacon {
+            round,
+            previous_signature,
+            signature,
+        } => execute_submit_drand_beacon(deps, round, previous_signature, signature),
+        ExecuteMsg::UpdateSaleConfig { config } => execute_update_sale_config(deps, info, config),
+        ExecuteMsg::BuyEgg { quantity } => execute_buy_egg(deps, env, info, quantity),
+        ExecuteMsg::Receive(wrapper) => execute_receive(deps, env, info, wrapper),
     }
 }
 
@@ -92,6 +162,14 @@ fn execute_mint(
     msg: CustomMintMsg,
 ) -> Result<Response, ContractError> {
     OWNED_EGG_COUNT.update::<_, StdError>(deps.storage, |id| Ok(id.add(Uint64::new(1))))?;
+    let hatch_at = match msg.hatch {
+        Some(hatch) => hatch
+            .parse::<u64>()
+            .map(Uint64::new)
+            .map_err(|_| ContractError::InvalidHatchTime { value: hatch })?,
+        None => incubated_at(deps.storage, &env)?,
+    };
+    HATCH_TIMES.save(deps.storage, &msg.base.token_id, &hatch_at)?;
 
     let mint_msg = Cw721ExecuteMsg::Mint(msg.base.clone());
     let mint_res = Cw721Contract::default().execute(deps, env.clone(), info.clone(), mint_msg);
@@ -103,6 +181,607 @@ fn execute_mint(
     Ok(Response::default().add_attribute("new owner", msg.base.owner.clone()))
 }
 
+fn execute_update_dragon_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let minter = Cw721Contract::default().minter.load(deps.storage)?;
+    if info.sender != minter {
+        return Err(ContractError::Unauthorized {});
+    }
+    let dragon_contract = deps.api.addr_validate(&address)?;
+    DRAGON_CONTRACT.save(deps.storage, &dragon_contract)?;
+    if let Some(config) = HATCH_CONFIG.may_load(deps.storage)? {
+        validate_hatch_kinds(deps.as_ref(), &config)?;
+    }
+    Ok(Response::new()
+        .add_attribute("action", "update_dragon_contract")
+        .add_attribute("dragon_contract", dragon_contract))
+}
+
+fn execute_dragon_birth(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    owner: String,
+    parents: Vec<DragonParent>,
+) -> Result<Response, ContractError> {
+    match DRAGON_CONTRACT.may_load(deps.storage)? {
+        Some(dragon_contract) if info.sender == dragon_contract => {}
+        _ => return Err(ContractError::Unauthorized {}),
+    }
+    if BIRTH_IDS.has(deps.storage, &id) {
+        return Err(ContractError::DuplicateBirth { id });
+    }
+    BIRTH_IDS.save(deps.storage, &id, &Empty {})?;
+    let hatch_at = incubated_at(deps.storage, &env)?;
+    HATCH_TIMES.save(deps.storage, &id, &hatch_at)?;
+
+    let origin = if parents.len() > 1 { "bred" } else { "planted" };
+    if parents.len() > 1 {
+        let kinds: Vec<String> = parents.iter().map(|parent| parent.kind.clone()).collect();
+        PARENT_KINDS.save(deps.storage, &id, &kinds)?;
+    }
+    let mut attributes = vec![egg_trait("origin", origin.to_string())];
+    for (i, parent) in parents.iter().enumerate() {
+        let position = i + 1;
+        attributes.push(egg_trait(
+            &format!("parent_{}", position),
+            parent.token_id.clone(),
+        ));
+        attributes.push(egg_trait(
+            &format!("parent_{}_kind", position),
+            parent.kind.clone(),
+        ));
+    }
+    let cw721 = Cw721Contract::default();
+    // the birth is minted on behalf of the cw721 minter, the only sender cw721-base accepts
+    let minter = cw721.minter.load(deps.storage)?;
+    let mint_msg = Cw721ExecuteMsg::Mint(cw721_base::MintMsg {
+        token_id: id.clone(),
+        owner: owner.clone(),
+        token_uri: None,
+        extension: Some(Metadata {
+            attributes: Some(attributes),
+            ..Metadata::default()
+        }),
+    });
+    let mint_info = MessageInfo {
+        sender: minter,
+        funds: vec![],
+    };
+    cw721.execute(deps, env, mint_info, mint_msg)?;
+    Ok(Response::new()
+        .add_attribute("action", "dragon_birth")
+        .add_attribute("token_id", id)
+        .add_attribute("owner", owner)
+        .add_attribute("origin", origin))
+}
+
+fn execute_update_hatch_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: HatchConfig,
+) -> Result<Response, ContractError> {
+    let minter = Cw721Contract::default().minter.load(deps.storage)?;
+    if info.sender != minter {
+        return Err(ContractError::Unauthorized {});
+    }
+    config.validate()?;
+    validate_hatch_kinds(deps.as_ref(), &config)?;
+    HATCH_CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("action", "update_hatch_config")
+        .add_attribute("incubation_period", config.incubation_period))
+}
+
+fn execute_commit_hatch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    commitment: Binary,
+) -> Result<Response, ContractError> {
+    let config = HATCH_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::HatchingDisabled {})?;
+    if DRAGON_CONTRACT.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::HatchingDisabled {});
+    }
+    if commitment.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+    let cw721 = Cw721Contract::default();
+    let token = cw721.tokens.load(deps.storage, &token_id)?;
+    cw721
+        .check_can_send(deps.as_ref(), &env, &info, &token)
+        .map_err(|_| ContractError::Unauthorized {})?;
+    let hatch_at = HATCH_TIMES
+        .may_load(deps.storage, &token_id)?
+        .unwrap_or_default();
+    if env.block.time.seconds() < hatch_at.u64() {
+        return Err(ContractError::EggNotReady { hatch_at });
+    }
+    if let Some(commit) = HATCH_COMMITS.may_load(deps.storage, &token_id)? {
+        return Err(ContractError::HatchCommitmentPending {
+            token_id,
+            reveal_from: commit.reveal_from(&config),
+            stale_from: commit.stale_from(&config),
+        });
+    }
+    if let Some(round) = DRAND_HATCH_ROUNDS.may_load(deps.storage, &token_id)? {
+        return Err(ContractError::DrandHatchPending { token_id, round });
+    }
+
+    let commit = HatchCommit {
+        commitment,
+        committer: info.sender,
+        height: env.block.height,
+        time: env.block.time,
+        nonce: bump_hatch_nonce(deps.storage)?,
+    };
+    HATCH_COMMITS.save(deps.storage, &token_id, &commit)?;
+    Ok(Response::new()
+        .add_attribute("action", "commit_hatch")
+        .add_attribute("token_id", token_id)
+        .add_attribute("reveal_from", commit.reveal_from(&config).to_string())
+        .add_attribute("stale_from", commit.stale_from(&config).to_string()))
+}
+
+fn execute_hatch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    secret: Binary,
+) -> Result<Response, ContractError> {
+    let config = HATCH_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::HatchingDisabled {})?;
+    let dragon_contract = DRAGON_CONTRACT
+        .may_load(deps.storage)?
+        .ok_or(ContractError::HatchingDisabled {})?;
+    let commit = HATCH_COMMITS
+        .may_load(deps.storage, &token_id)?
+        .ok_or_else(|| ContractError::NoHatchCommitment {
+            token_id: token_id.clone(),
+        })?;
+    let reveal_from = commit.reveal_from(&config);
+    if env.block.height < reveal_from {
+        return Err(ContractError::RevealTooEarly { reveal_from });
+    }
+    if !commit.matches(&secret) {
+        return Err(ContractError::InvalidReveal {});
+    }
+
+    let cw721 = Cw721Contract::default();
+    let token = cw721.tokens.load(deps.storage, &token_id)?;
+    cw721
+        .check_can_send(deps.as_ref(), &env, &info, &token)
+        .map_err(|_| ContractError::Unauthorized {})?;
+    let owner = token.owner;
+
+    let parent_kinds = PARENT_KINDS
+        .may_load(deps.storage, &token_id)?
+        .unwrap_or_default();
+    let rarity = config.draw(reveal_roll(&commit, &secret, &token_id), &parent_kinds);
+    burn_hatched_egg(deps, env, &token_id, &owner)?;
+    let mint_msg = dragon_mint_msg(&owner, rarity, &dragon_contract)?;
+    Ok(Response::new()
+        .add_attribute("action", "hatch")
+        .add_attribute("token_id", token_id)
+        .add_attribute("owner", owner)
+        .add_attribute("kind", rarity.kind.clone())
+        .add_message(mint_msg))
+}
+
+fn execute_update_drand_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<DrandConfig>,
+) -> Result<Response, ContractError> {
+    let minter = Cw721Contract::default().minter.load(deps.storage)?;
+    if info.sender != minter {
+        return Err(ContractError::Unauthorized {});
+    }
+    // waiting eggs can only be hatched by beacons of the network they committed to
+    let waiting = DRAND_HATCH_ROUNDS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    if waiting && DRAND_CONFIG.may_load(deps.storage)? != config {
+        return Err(ContractError::DrandHatchesWaiting {});
+    }
+    match config {
+        Some(config) => {
+            config.validate()?;
+            DRAND_CONFIG.save(deps.storage, &config)?;
+        }
+        None => DRAND_CONFIG.remove(deps.storage),
+    }
+    Ok(Response::new().add_attribute("action", "update_drand_config"))
+}
+
+fn execute_commit_drand_hatch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let config = HATCH_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::HatchingDisabled {})?;
+    if DRAGON_CONTRACT.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::HatchingDisabled {});
+    }
+    let drand_config = DRAND_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::DrandDisabled {})?;
+    let cw721 = Cw721Contract::default();
+    let token = cw721.tokens.load(deps.storage, &token_id)?;
+    cw721
+        .check_can_send(deps.as_ref(), &env, &info, &token)
+        .map_err(|_| ContractError::Unauthorized {})?;
+    let hatch_at = HATCH_TIMES
+        .may_load(deps.storage, &token_id)?
+        .unwrap_or_default();
+    if env.block.time.seconds() < hatch_at.u64() {
+        return Err(ContractError::EggNotReady { hatch_at });
+    }
+    // a stale commitment, e.g. of a lost secret, gives way to the drand round
+    if let Some(commit) = HATCH_COMMITS.may_load(deps.storage, &token_id)? {
+        if env.block.height < commit.stale_from(&config) {
+            return Err(ContractError::HatchCommitmentPending {
+                token_id,
+                reveal_from: commit.reveal_from(&config),
+                stale_from: commit.stale_from(&config),
+            });
+        }
+        HATCH_COMMITS.remove(deps.storage, &token_id);
+    }
+    if let Some(round) = DRAND_HATCH_ROUNDS.may_load(deps.storage, &token_id)? {
+        return Err(ContractError::DrandHatchPending { token_id, round });
+    }
+
+    let round = drand_config.settling_round(env.block.time.seconds());
+    DRAND_HATCHES.save(deps.storage, (round, &token_id), &Empty {})?;
+    DRAND_HATCH_ROUNDS.save(deps.storage, &token_id, &round)?;
+    Ok(Response::new()
+        .add_attribute("action", "commit_drand_hatch")
+        .add_attribute("token_id", token_id)
+        .add_attribute("round", round.to_string()))
+}
+
+fn execute_submit_drand_beacon(
+    deps: DepsMut,
+    round: u64,
+    previous_signature: Option<Binary>,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    let config = HATCH_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::HatchingDisabled {})?;
+    let dragon_contract = DRAGON_CONTRACT
+        .may_load(deps.storage)?
+        .ok_or(ContractError::HatchingDisabled {})?;
+    let drand_config = DRAND_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::DrandDisabled {})?;
+    let randomness = match DRAND_BEACONS.may_load(deps.storage, round)? {
+        Some(randomness) => randomness,
+        None => {
+            let previous_signature = previous_signature.unwrap_or_default();
+            let randomness = drand_config.verify(round, &previous_signature, &signature)?;
+            let randomness = Binary::from(randomness.to_vec());
+            DRAND_BEACONS.save(deps.storage, round, &randomness)?;
+            randomness
+        }
+    };
+
+    let token_ids = DRAND_HATCHES
+        .prefix(round)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(MAX_DRAND_HATCHES)
+        .collect::<StdResult<Vec<_>>>()?;
+    let cw721 = Cw721Contract::default();
+    let mut res = Response::new()
+        .add_attribute("action", "submit_drand_beacon")
+        .add_attribute("round", round.to_string());
+    let mut reply_id = 0;
+    for token_id in token_ids {
+        // the egg may have been burnt while waiting
+        let owner = match cw721.tokens.may_load(deps.storage, &token_id)? {
+            Some(token) => token.owner,
+            None => {
+                DRAND_HATCHES.remove(deps.storage, (round, &token_id));
+                DRAND_HATCH_ROUNDS.remove(deps.storage, &token_id);
+                continue;
+            }
+        };
+        let parent_kinds = PARENT_KINDS
+            .may_load(deps.storage, &token_id)?
+            .unwrap_or_default();
+        let rarity = config.draw(beacon_roll(&randomness, &token_id), &parent_kinds);
+        let mint_msg = dragon_mint_msg(&owner, rarity, &dragon_contract)?;
+        // each egg is only burnt once its dragon is minted, so one failing mint leaves the
+        // others hatched and keeps its own egg waiting for the next submission
+        reply_id += 1;
+        let hatch = PendingHatch {
+            token_id,
+            owner,
+            kind: rarity.kind.clone(),
+        };
+        PENDING_HATCHES.save(deps.storage, reply_id, &hatch)?;
+        res = res.add_submessage(SubMsg::reply_always(mint_msg, reply_id));
+    }
+    Ok(res.add_attribute("hatching", reply_id.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let hatch = PENDING_HATCHES
+        .may_load(deps.storage, msg.id)?
+        .ok_or(ContractError::UnknownReplyId { id: msg.id })?;
+    PENDING_HATCHES.remove(deps.storage, msg.id);
+    let event = Event::new("hatch")
+        .add_attribute("token_id", hatch.token_id.clone())
+        .add_attribute("owner", hatch.owner.clone())
+        .add_attribute("kind", hatch.kind);
+    match msg.result {
+        SubMsgResult::Ok(_) => {
+            if let Some(round) = DRAND_HATCH_ROUNDS.may_load(deps.storage, &hatch.token_id)? {
+                DRAND_HATCHES.remove(deps.storage, (round, &hatch.token_id));
+                DRAND_HATCH_ROUNDS.remove(deps.storage, &hatch.token_id);
+            }
+            burn_hatched_egg(deps, env, &hatch.token_id, &hatch.owner)?;
+            Ok(Response::new().add_event(event))
+        }
+        // the egg keeps waiting for its round
+        SubMsgResult::Err(err) => Ok(Response::new().add_event(event.add_attribute("error", err))),
+    }
+}
+
+/// Burns the hatched egg of `owner`.
+fn burn_hatched_egg(
+    deps: DepsMut,
+    env: Env,
+    token_id: &str,
+    owner: &Addr,
+) -> Result<(), ContractError> {
+    HATCH_TIMES.remove(deps.storage, token_id);
+    HATCH_COMMITS.remove(deps.storage, token_id);
+    PARENT_KINDS.remove(deps.storage, token_id);
+    let burn_msg = Cw721ExecuteMsg::Burn {
+        token_id: token_id.to_string(),
+    };
+    // callers checked the hatch is allowed, the egg is burnt on behalf of its owner
+    let owner_info = MessageInfo {
+        sender: owner.clone(),
+        funds: vec![],
+    };
+    Cw721Contract::default().execute(deps, env, owner_info, burn_msg)?;
+    Ok(())
+}
+
+/// Message minting the dragon hatched from an egg of `owner`.
+fn dragon_mint_msg(owner: &Addr, rarity: &Rarity, dragon_contract: &Addr) -> StdResult<WasmMsg> {
+    let mint_msg = DragonExecuteMsg::Mint(DragonMintMsg {
+        base: cw721_base::MintMsg {
+            token_id: String::new(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        },
+        extension: rarity.to_traits(),
+    });
+    Ok(WasmMsg::Execute {
+        contract_addr: dragon_contract.to_string(),
+        msg: to_binary(&mint_msg)?,
+        funds: vec![],
+    })
+}
+
+fn bump_hatch_nonce(storage: &mut dyn Storage) -> StdResult<u64> {
+    let nonce = HATCH_NONCE
+        .may_load(storage)?
+        .unwrap_or_default()
+        .wrapping_add(1);
+    HATCH_NONCE.save(storage, &nonce)?;
+    Ok(nonce)
+}
+
+fn execute_update_sale_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<SaleConfig>,
+) -> Result<Response, ContractError> {
+    let minter = Cw721Contract::default().minter.load(deps.storage)?;
+    if info.sender != minter {
+        return Err(ContractError::Unauthorized {});
+    }
+    match config {
+        Some(config) => {
+            config.validate(deps.api)?;
+            SALE_CONFIG.save(deps.storage, &config)?;
+        }
+        None => SALE_CONFIG.remove(deps.storage),
+    }
+    Ok(Response::new().add_attribute("action", "update_sale_config"))
+}
+
+fn execute_buy_egg(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    quantity: u32,
+) -> Result<Response, ContractError> {
+    let config = SALE_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::SaleClosed {})?;
+    let price = sale_price(deps.storage, quantity)?;
+    let denom = match &config.payment {
+        SalePayment::Native { denom } => denom.clone(),
+        SalePayment::Cw20 { address } => {
+            return Err(ContractError::InvalidPayment {
+                expected: format!("{} of cw20 {}", price, address),
+            })
+        }
+    };
+    if price.is_zero() {
+        nonpayable(&info)?;
+        return sell_eggs(deps, env, &config, info.sender, quantity);
+    }
+    if must_pay(&info, &denom)? != price {
+        return Err(ContractError::InvalidPayment {
+            expected: format!("{}{}", price, denom),
+        });
+    }
+    let res = sell_eggs(deps, env, &config, info.sender, quantity)?;
+    Ok(res.add_message(BankMsg::Send {
+        to_address: config.treasury,
+        amount: coins(price.u128(), denom),
+    }))
+}
+
+fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let msg: ReceiveMsg = from_slice(&wrapper.msg)?;
+    match msg {
+        ReceiveMsg::BuyEgg { quantity } => execute_buy_egg_cw20(deps, env, info, wrapper, quantity),
+    }
+}
+
+fn execute_buy_egg_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+    quantity: u32,
+) -> Result<Response, ContractError> {
+    let config = SALE_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::SaleClosed {})?;
+    let price = sale_price(deps.storage, quantity)?;
+    let expected = match &config.payment {
+        SalePayment::Native { denom } => format!("{}{}", price, denom),
+        SalePayment::Cw20 { address } => format!("{} of cw20 {}", price, address),
+    };
+    // the sender is the token contract, the buyer is whoever sent the tokens
+    match &config.payment {
+        SalePayment::Cw20 { address } if info.sender == *address && wrapper.amount == price => {}
+        _ => return Err(ContractError::InvalidPayment { expected }),
+    }
+    let buyer = deps.api.addr_validate(&wrapper.sender)?;
+    let res = sell_eggs(deps, env, &config, buyer, quantity)?;
+    if price.is_zero() {
+        return Ok(res);
+    }
+    Ok(res.add_message(WasmMsg::Execute {
+        contract_addr: info.sender.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: config.treasury,
+            amount: price,
+        })?,
+        funds: vec![],
+    }))
+}
+
+/// Price of `quantity` eggs at the base price.
+fn sale_price(storage: &dyn Storage, quantity: u32) -> StdResult<Uint128> {
+    let base_price = COLLECTION_INFO.load(storage)?.base_price;
+    Ok(Uint128::from(base_price.u64()).checked_mul(Uint128::from(quantity))?)
+}
+
+/// Mints `quantity` eggs to `buyer` within the collection size and the wallet cap.
+fn sell_eggs(
+    mut deps: DepsMut,
+    env: Env,
+    config: &SaleConfig,
+    buyer: Addr,
+    quantity: u32,
+) -> Result<Response, ContractError> {
+    if quantity == 0 {
+        return Err(ContractError::InvalidQuantity {});
+    }
+    let collection = COLLECTION_INFO.load(deps.storage)?;
+    let owned = OWNED_EGG_COUNT.load(deps.storage)?;
+    let remaining = collection.size.u64().saturating_sub(owned.u64());
+    if u64::from(quantity) > remaining {
+        return Err(ContractError::SoldOut { remaining });
+    }
+    let purchased = PURCHASES
+        .may_load(deps.storage, &buyer)?
+        .unwrap_or_default();
+    if purchased.saturating_add(quantity) > config.wallet_cap {
+        return Err(ContractError::WalletCapExceeded {
+            cap: config.wallet_cap,
+        });
+    }
+    PURCHASES.save(deps.storage, &buyer, &(purchased + quantity))?;
+    OWNED_EGG_COUNT.save(deps.storage, &owned.add(Uint64::from(quantity)))?;
+
+    let cw721 = Cw721Contract::default();
+    let minter = cw721.minter.load(deps.storage)?;
+    let hatch_at = incubated_at(deps.storage, &env)?;
+    let mut seq = SALE_TOKEN_SEQ.may_load(deps.storage)?.unwrap_or_default();
+    let mut token_ids = vec![];
+    for _ in 0..quantity {
+        // ids minted outside of the sale are skipped
+        seq += 1;
+        while cw721
+            .tokens
+            .may_load(deps.storage, &seq.to_string())?
+            .is_some()
+        {
+            seq += 1;
+        }
+        let token_id = seq.to_string();
+        HATCH_TIMES.save(deps.storage, &token_id, &hatch_at)?;
+        let mint_msg = Cw721ExecuteMsg::Mint(cw721_base::MintMsg {
+            token_id: token_id.clone(),
+            owner: buyer.to_string(),
+            token_uri: Some(config.token_uri(&token_id)),
+            extension: None,
+        });
+        // sold eggs are minted on behalf of the cw721 minter, like births
+        let mint_info = MessageInfo {
+            sender: minter.clone(),
+            funds: vec![],
+        };
+        cw721.execute(deps.branch(), env.clone(), mint_info, mint_msg)?;
+        token_ids.push(token_id);
+    }
+    SALE_TOKEN_SEQ.save(deps.storage, &seq)?;
+    Ok(Response::new()
+        .add_attribute("action", "buy_egg")
+        .add_attribute("buyer", buyer)
+        .add_attribute("quantity", quantity.to_string())
+        .add_attribute("token_ids", token_ids.join(",")))
+}
+
+/// Hatch time of an egg minted now.
+fn incubated_at(storage: &dyn Storage, env: &Env) -> StdResult<Uint64> {
+    let incubation_period = HATCH_CONFIG
+        .may_load(storage)?
+        .map(|config| config.incubation_period)
+        .unwrap_or_default();
+    Ok(Uint64::new(env.block.time.seconds()).checked_add(incubation_period)?)
+}
+
+fn egg_trait(trait_type: &str, value: String) -> Trait {
+    Trait {
+        display_type: None,
+        trait_type: trait_type.to_string(),
+        value,
+    }
+}
+
 fn execute_approve_all(
     deps: DepsMut,
     env: Env,
@@ -195,6 +874,9 @@ fn execute_send_nft(
     token_id: String,
     msg: Binary,
 ) -> Result<Response, ContractError> {
+    if HATCH_COMMITS.has(deps.storage, &token_id) {
+        return Err(ContractError::HatchCommittedEggCantBeTransferred { token_id });
+    }
     let send_msg = Cw721ExecuteMsg::SendNft {
         contract,
         token_id,
@@ -216,6 +898,9 @@ fn execute_transfer_nft(
     recipient: String,
     token_id: String,
 ) -> Result<Response, ContractError> {
+    if HATCH_COMMITS.has(deps.storage, &token_id) {
+        return Err(ContractError::HatchCommittedEggCantBeTransferred { token_id });
+    }
     let msg = Cw721ExecuteMsg::TransferNft {
         recipient,
         token_id,
@@ -229,11 +914,56 @@ fn execute_transfer_nft(
     Ok(Response::default().add_attribute("trasnfer nft", "success"))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    // v0.1 instances were left with the version written by cw721-base, which any cw721 shares,
+    // so that name only counts over egg-mint's own state
+    let legacy = OWNED_EGG_COUNT
+        .may_load(deps.storage)?
+        .map(|_| LEGACY_CONTRACT_NAME);
+    stored_version(
+        &stored.contract,
+        &stored.version,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        legacy,
+    )?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::CollectionInfo {} => to_binary(&query_config(deps)?),
         QueryMsg::OwnedEggCount {} => to_binary(&query_owned_egg_count(deps)?),
+        QueryMsg::DragonContract {} => to_binary(&DragonContractResponse {
+            dragon_contract: DRAGON_CONTRACT.may_load(deps.storage)?.map(String::from),
+        }),
+        QueryMsg::HatchConfig {} => to_binary(&HATCH_CONFIG.may_load(deps.storage)?),
+        QueryMsg::HatchCommit { token_id } => {
+            to_binary(&HATCH_COMMITS.may_load(deps.storage, &token_id)?)
+        }
+        QueryMsg::DrandConfig {} => to_binary(&DRAND_CONFIG.may_load(deps.storage)?),
+        QueryMsg::DrandHatch { token_id } => {
+            to_binary(&DRAND_HATCH_ROUNDS.may_load(deps.storage, &token_id)?)
+        }
+        QueryMsg::DrandBeacon { round } => to_binary(&DRAND_BEACONS.may_load(deps.storage, round)?),
+        QueryMsg::SaleConfig {} => to_binary(&SALE_CONFIG.may_load(deps.storage)?),
+        QueryMsg::Purchased { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_binary(
+                &PURCHASES
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default(),
+            )
+        }
+        QueryMsg::Incubation { token_id } => to_binary(&query_incubation(deps, env, token_id)?),
         _ => Cw721Contract::default().query(deps, env, msg.into()),
     }
 }
@@ -260,3 +990,19 @@ pub fn query_owned_egg_count(deps: Deps) -> StdResult<OwnedEggInfoResponse> {
         size: info.size,
     })
 }
+
+fn query_incubation(deps: Deps, env: Env, token_id: String) -> StdResult<IncubationResponse> {
+    // errors for eggs that do not exist
+    Cw721Contract::default()
+        .tokens
+        .load(deps.storage, &token_id)?;
+    // eggs minted before incubation existed can hatch right away
+    let hatch_at = HATCH_TIMES
+        .may_load(deps.storage, &token_id)?
+        .unwrap_or_default();
+    Ok(IncubationResponse {
+        token_id,
+        hatch_at,
+        ready: env.block.time.seconds() >= hatch_at.u64(),
+    })
+}
@@ -0,0 +1,87 @@
+use crate::ContractError;
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{G1Affine, G2Affine, G2Projective};
+use cosmwasm_std::Binary;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::convert::{TryFrom, TryInto};
+
+/// Domain separation tag of drand signatures on G2.
+const DOMAIN: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// drand network whose beacons settle hatches. Its signatures are on G2, as on the drand
+/// mainnet.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DrandConfig {
+    /// Compressed G1 public key of the network
+    pub public_key: Binary,
+    /// Unix time in seconds of round 1
+    pub genesis_time: u64,
+    /// Seconds between two rounds
+    pub period: u64,
+}
+
+impl DrandConfig {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        let invalid = |reason: &str| ContractError::InvalidDrandConfig {
+            reason: reason.to_string(),
+        };
+        if self.period == 0 {
+            return Err(invalid("period cannot be zero"));
+        }
+        if self.public_key().is_none() {
+            return Err(invalid("public key is not a compressed G1 point"));
+        }
+        Ok(())
+    }
+
+    fn public_key(&self) -> Option<G1Affine> {
+        let bytes: [u8; 48] = self.public_key.as_slice().try_into().ok()?;
+        Option::from(G1Affine::from_compressed(&bytes))
+    }
+
+    /// Latest round published at `time`.
+    pub fn round_at(&self, time: u64) -> u64 {
+        match time.checked_sub(self.genesis_time) {
+            Some(elapsed) => elapsed / self.period + 1,
+            None => 0,
+        }
+    }
+
+    /// Round whose randomness settles a hatch committed at `time`. It is published at least one
+    /// period after `time`, leaving room for block times lagging behind the network.
+    pub fn settling_round(&self, time: u64) -> u64 {
+        self.round_at(time) + 2
+    }
+
+    /// Randomness of `round` once its signature is checked against the public key.
+    /// `previous_signature` is only part of the signed message on chained networks.
+    pub fn verify(
+        &self,
+        round: u64,
+        previous_signature: &[u8],
+        signature: &[u8],
+    ) -> Result<[u8; 32], ContractError> {
+        let public_key = self
+            .public_key()
+            .ok_or(ContractError::InvalidBeacon { round })?;
+        let signature_point = <[u8; 96]>::try_from(signature)
+            .ok()
+            .and_then(|bytes| Option::from(G2Affine::from_compressed(&bytes)))
+            .ok_or(ContractError::InvalidBeacon { round })?;
+        let message = Sha256::new()
+            .chain_update(previous_signature)
+            .chain_update(round.to_be_bytes())
+            .finalize();
+        let message_point = G2Affine::from(<G2Projective as HashToCurve<
+            ExpandMsgXmd<sha2_v9::Sha256>,
+        >>::hash_to_curve(message, DOMAIN));
+        if bls12_381::pairing(&G1Affine::generator(), &signature_point)
+            != bls12_381::pairing(&public_key, &message_point)
+        {
+            return Err(ContractError::InvalidBeacon { round });
+        }
+        Ok(Sha256::digest(signature).into())
+    }
+}
@@ -1,6 +1,7 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint64};
 use cw721_base::ContractError as Cw721ContractError;
 use cw_utils::PaymentError;
+use stake_dragons_utils::migrate::MigrateError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -29,8 +30,87 @@ pub enum ContractError {
     #[error("721 error : {method}")]
     NftContractError { method: String },
 
+    #[error("{0}")]
+    Cw721(#[from] Cw721ContractError),
+
     #[error("{0}")]
     Payment(#[from] PaymentError),
+
+    #[error("Egg {id} was already born")]
+    DuplicateBirth { id: String },
+
+    #[error("Invalid hatch config: {reason}")]
+    InvalidHatchConfig { reason: String },
+
+    #[error("Hatching is not configured")]
+    HatchingDisabled {},
+
+    #[error("Invalid hatch time: {value}")]
+    InvalidHatchTime { value: String },
+
+    #[error("Egg can hatch from {hatch_at}")]
+    EggNotReady { hatch_at: Uint64 },
+
+    #[error("Commitment must be a sha256 hash")]
+    InvalidCommitment {},
+
+    #[error("Egg {token_id} has no hatch commitment")]
+    NoHatchCommitment { token_id: String },
+
+    #[error("Egg {token_id} has a hatch commitment to reveal from block {reveal_from}, or to hatch with drand from block {stale_from}")]
+    HatchCommitmentPending {
+        token_id: String,
+        reveal_from: u64,
+        stale_from: u64,
+    },
+
+    #[error("Egg {token_id} cannot change hands while its hatch is committed")]
+    HatchCommittedEggCantBeTransferred { token_id: String },
+
+    #[error("Hatch can be revealed from block {reveal_from}")]
+    RevealTooEarly { reveal_from: u64 },
+
+    #[error("Secret does not match the commitment")]
+    InvalidReveal {},
+
+    #[error("Invalid drand config: {reason}")]
+    InvalidDrandConfig { reason: String },
+
+    #[error("drand randomness is not configured")]
+    DrandDisabled {},
+
+    #[error("Egg {token_id} waits for drand round {round}")]
+    DrandHatchPending { token_id: String, round: u64 },
+
+    #[error("Eggs are waiting for rounds of the current drand network")]
+    DrandHatchesWaiting {},
+
+    #[error("Invalid drand beacon for round {round}")]
+    InvalidBeacon { round: u64 },
+
+    #[error("Invalid sale config: {reason}")]
+    InvalidSaleConfig { reason: String },
+
+    #[error("Egg sale is closed")]
+    SaleClosed {},
+
+    #[error("Quantity must be at least one")]
+    InvalidQuantity {},
+
+    #[error("Only {remaining} eggs are left")]
+    SoldOut { remaining: u64 },
+
+    #[error("A wallet can buy at most {cap} eggs")]
+    WalletCapExceeded { cap: u32 },
+
+    #[error("Expected a payment of {expected}")]
+    InvalidPayment { expected: String },
+
+    #[error("Unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("{0}")]
+    Migrate(#[from] MigrateError),
 }
 
 impl From<ContractError> for Cw721ContractError {
@@ -38,6 +118,7 @@ impl From<ContractError> for Cw721ContractError {
         match err {
             ContractError::Unauthorized {} => Cw721ContractError::Unauthorized {},
             ContractError::Claimed {} => Cw721ContractError::Claimed {},
+            ContractError::Cw721(err) => err,
             _ => unreachable!("cannot connect {:?} to cw721ContractError", err),
         }
     }
@@ -0,0 +1,166 @@
+use crate::msg::Trait;
+use crate::ContractError;
+use cosmwasm_std::{Addr, Binary, Timestamp, Uint128, Uint64};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use stake_dragons_utils::traits::{is_valid_kind, is_valid_ovulation_period, MAX_OVULATION_PERIOD};
+
+/// Traits of a dragon hatched with the given odds.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Rarity {
+    pub kind: String,
+    /// Chance to be drawn relative to the weights of the other rarities.
+    pub weight: u32,
+    /// Days between two eggs of the hatched dragon.
+    pub ovulation_period: u64,
+    pub daily_income: Uint128,
+}
+
+impl Rarity {
+    /// Traits sent to dragon-mint's `Mint`.
+    pub fn to_traits(&self) -> Vec<Trait> {
+        vec![
+            ("kind", self.kind.clone()),
+            ("ovulation_period", self.ovulation_period.to_string()),
+            ("daily_income", self.daily_income.to_string()),
+        ]
+        .into_iter()
+        .map(|(trait_type, value)| Trait {
+            display_type: None,
+            trait_type: trait_type.to_string(),
+            value,
+        })
+        .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HatchConfig {
+    /// Seconds between minting an egg and being able to hatch it.
+    pub incubation_period: Uint64,
+    /// Blocks between committing to a hatch and revealing it.
+    pub reveal_delay: u64,
+    /// Blocks after which a commitment that was not revealed goes stale and its egg can be
+    /// hatched with drand instead, e.g. when the secret was lost.
+    pub stale_after: u64,
+    pub rarities: Vec<Rarity>,
+}
+
+impl HatchConfig {
+    /// Checks the config on its own. Whether dragon-mint knows the kinds is checked by the
+    /// contract, once the dragon contract is set.
+    pub fn validate(&self) -> Result<(), ContractError> {
+        let invalid = |reason: &str| ContractError::InvalidHatchConfig {
+            reason: reason.to_string(),
+        };
+        if self.reveal_delay == 0 {
+            return Err(invalid("reveal delay must be at least one block"));
+        }
+        if self.stale_after <= self.reveal_delay {
+            return Err(invalid("commitments must go stale after the reveal delay"));
+        }
+        if self.rarities.iter().all(|rarity| rarity.weight == 0) {
+            return Err(invalid("at least one rarity needs a weight"));
+        }
+        for rarity in &self.rarities {
+            if !is_valid_kind(&rarity.kind) {
+                return Err(invalid(&format!("invalid kind {:?}", rarity.kind)));
+            }
+            if !is_valid_ovulation_period(rarity.ovulation_period) {
+                return Err(invalid(&format!(
+                    "ovulation period must be between 1 and {} days",
+                    MAX_OVULATION_PERIOD
+                )));
+            }
+            if rarity.daily_income.is_zero() {
+                return Err(invalid("daily income cannot be zero"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Rarity whose share of the total weight `roll` falls into. Each parent of a bred egg
+    /// adds the weight of its own kind once more, so eggs take after their parents.
+    pub fn draw(&self, roll: u64, parent_kinds: &[String]) -> &Rarity {
+        let weights: Vec<u64> = self
+            .rarities
+            .iter()
+            .map(|rarity| {
+                let parents = parent_kinds.iter().filter(|k| **k == rarity.kind).count();
+                u64::from(rarity.weight) * (1 + parents as u64)
+            })
+            .collect();
+        let total: u64 = weights.iter().sum();
+        let mut point = roll % total;
+        for (rarity, weight) in self.rarities.iter().zip(weights) {
+            if point < weight {
+                return rarity;
+            }
+            point -= weight;
+        }
+        unreachable!("point is below the total weight")
+    }
+}
+
+/// Hash of a secret an owner commits to before hatching an egg. A commitment stands until it is
+/// revealed or goes stale and is handed to drand. Meanwhile the egg cannot be committed again
+/// or change hands.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HatchCommit {
+    /// sha256 of the secret revealed by `Hatch`
+    pub commitment: Binary,
+    pub committer: Addr,
+    /// Height of the block the commitment was made in
+    pub height: u64,
+    /// Time of the block the commitment was made in
+    pub time: Timestamp,
+    /// Hatch nonce once the commitment was counted
+    pub nonce: u64,
+}
+
+impl HatchCommit {
+    /// First block the secret can be revealed in.
+    pub fn reveal_from(&self, config: &HatchConfig) -> u64 {
+        self.height + config.reveal_delay
+    }
+
+    /// First block the egg can be hatched with drand instead.
+    pub fn stale_from(&self, config: &HatchConfig) -> u64 {
+        self.height + config.stale_after
+    }
+
+    pub fn matches(&self, secret: &[u8]) -> bool {
+        Sha256::digest(secret).as_slice() == self.commitment.as_slice()
+    }
+}
+
+/// Roll of a revealed hatch. It only depends on the commitment: the secret, picked before the
+/// owner knows the time of the block its commitment lands in or the nonce it gets there. The
+/// block it is revealed in does not matter and a commitment cannot be replaced, so the owner can
+/// neither pick nor re-roll the outcome. Giving up a stale commitment for drand only trades it
+/// for a roll nobody knows yet, after waiting `stale_after` blocks. The proposer of the
+/// commitment's block can still grind its own hatches, which drand hatches avoid.
+pub fn reveal_roll(commit: &HatchCommit, secret: &[u8], token_id: &str) -> u64 {
+    let hash = Sha256::new()
+        .chain_update(secret)
+        .chain_update(commit.height.to_be_bytes())
+        .chain_update(commit.time.nanos().to_be_bytes())
+        .chain_update(commit.nonce.to_be_bytes())
+        .chain_update(token_id.as_bytes())
+        .finalize();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash[..8]);
+    u64::from_be_bytes(bytes)
+}
+
+/// Roll of an egg settled by a drand round.
+pub fn beacon_roll(randomness: &[u8], token_id: &str) -> u64 {
+    let hash = Sha256::new()
+        .chain_update(randomness)
+        .chain_update(token_id.as_bytes())
+        .finalize();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash[..8]);
+    u64::from_be_bytes(bytes)
+}
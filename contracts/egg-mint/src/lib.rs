@@ -1,6 +1,9 @@
 pub mod contract;
+pub mod drand;
 mod error;
+pub mod hatch;
 pub mod msg;
+pub mod sale;
 pub mod state;
 pub mod tests;
 
@@ -1,4 +1,8 @@
+use crate::drand::DrandConfig;
+use crate::hatch::HatchConfig;
+use crate::sale::SaleConfig;
 use cosmwasm_std::{Binary, Uint64};
+use cw20::Cw20ReceiveMsg;
 
 use cw721_base::msg::InstantiateMsg as Cw721InstantiateMsg;
 use cw721_base::msg::MintMsg as Cw721MintMsg;
@@ -13,8 +17,14 @@ pub struct InstantiateMsg {
     pub base: Cw721InstantiateMsg,
     pub size: Uint64,
     pub base_price: Uint64,
+    /// dragon-mint contract allowed to send `DragonBirth`
+    pub dragon_contract: Option<String>,
+    pub hatch_config: Option<HatchConfig>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
 pub struct Trait {
     pub display_type: Option<String>,
@@ -38,9 +48,16 @@ pub struct Metadata {
 
 pub type Extension = Option<Metadata>;
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DragonParent {
+    pub token_id: String,
+    pub kind: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct CustomMintMsg {
     pub base: Cw721MintMsg<Extension>,
+    /// Unix time in seconds the egg can hatch from, by default once the incubation period passed
     pub hatch: Option<String>,
 }
 
@@ -79,6 +96,85 @@ pub enum ExecuteMsg {
 
     /// Mint a new NFT, can only be called by the contract minter
     Mint(CustomMintMsg),
+    /// Sets the dragon-mint contract allowed to send `DragonBirth`, minter only
+    UpdateDragonContract { address: String },
+    /// Mints the egg a dragon planted, or two dragons bred, to `owner`.
+    /// Only the dragon contract can send it and every `id` is minted once.
+    DragonBirth {
+        id: String,
+        owner: String,
+        #[serde(default)]
+        parents: Vec<DragonParent>,
+    },
+    /// Minter only
+    UpdateHatchConfig { config: HatchConfig },
+    /// Commits to the sha256 hash of a secret to hatch an incubated egg with. The commitment
+    /// fixes the rarity and cannot be replaced, and the egg cannot be transferred until it
+    /// hatches.
+    CommitHatch {
+        token_id: String,
+        commitment: Binary,
+    },
+    /// Reveals the committed secret, burning the egg and minting a dragon of a random rarity to its
+    /// owner. Valid from `reveal_delay` blocks after the commitment on.
+    Hatch { token_id: String, secret: Binary },
+    /// Minter only, `None` turns drand hatching off. Refused while eggs wait for a round, unless
+    /// the config stays the same.
+    UpdateDrandConfig { config: Option<DrandConfig> },
+    /// Hatches an incubated egg with the randomness of a future drand round instead of a secret.
+    /// Also takes over a commitment that went stale `stale_after` blocks after it was made.
+    CommitDrandHatch { token_id: String },
+    /// Verifies a drand beacon and hatches the eggs waiting for its round. An egg whose dragon
+    /// cannot be minted keeps waiting without failing the others. Submitting the beacon again
+    /// hatches the eggs left over by the previous submission.
+    SubmitDrandBeacon {
+        round: u64,
+        /// Signature of the previous round, on chained networks only
+        previous_signature: Option<Binary>,
+        signature: Binary,
+    },
+    /// Minter only, `None` closes the sale
+    UpdateSaleConfig { config: Option<SaleConfig> },
+    /// Buys `quantity` eggs at the base price each, paid in the native denom of the sale
+    BuyEgg { quantity: u32 },
+    /// Buys eggs with the cw20 token of the sale
+    Receive(Cw20ReceiveMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    BuyEgg { quantity: u32 },
+}
+
+/// dragon-mint's `Mint` message
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DragonMintMsg {
+    pub base: Cw721MintMsg<Extension>,
+    pub extension: Vec<Trait>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DragonExecuteMsg {
+    Mint(DragonMintMsg),
+}
+
+/// dragon-mint's queries egg-mint makes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum DragonQueryMsg {
+    RewardSchedules {},
+}
+
+/// dragon-mint's `RewardSchedulesResponse`, down to the kinds it can mint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DragonRewardSchedulesResponse {
+    pub schedules: Vec<DragonKind>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DragonKind {
+    pub kind: String,
 }
 
 /// cw721 query messages with custom messages
@@ -151,6 +247,32 @@ pub enum QueryMsg {
     Minter {},
     CollectionInfo {},
     OwnedEggCount {},
+    DragonContract {},
+    HatchConfig {},
+    /// Return type: `IncubationResponse`
+    Incubation {
+        token_id: String,
+    },
+    /// Return type: `Option<HatchCommit>`
+    HatchCommit {
+        token_id: String,
+    },
+    /// Return type: `Option<DrandConfig>`
+    DrandConfig {},
+    /// drand round an egg waits for. Return type: `Option<u64>`
+    DrandHatch {
+        token_id: String,
+    },
+    /// Verified randomness of a round. Return type: `Option<Binary>`
+    DrandBeacon {
+        round: u64,
+    },
+    /// Return type: `Option<SaleConfig>`
+    SaleConfig {},
+    /// Eggs bought by `address`. Return type: `u32`
+    Purchased {
+        address: String,
+    },
 }
 
 impl From<QueryMsg> for Cw721QueryMsg {
@@ -233,3 +355,16 @@ pub struct OwnedEggInfoResponse {
     pub owned: Uint64,
     pub size: Uint64,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DragonContractResponse {
+    pub dragon_contract: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IncubationResponse {
+    pub token_id: String,
+    /// Unix time in seconds the egg can hatch from
+    pub hatch_at: Uint64,
+    pub ready: bool,
+}
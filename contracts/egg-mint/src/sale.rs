@@ -0,0 +1,57 @@
+use crate::ContractError;
+use cosmwasm_std::Api;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Token eggs are paid in, `CollectionInfo.base_price` of it per egg.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SalePayment {
+    Native {
+        denom: String,
+    },
+    /// Paid through the token's `Send` with a `BuyEgg` receive message
+    Cw20 {
+        address: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SaleConfig {
+    pub payment: SalePayment,
+    /// Sold eggs get `{base_uri}/{token_id}` as token uri
+    pub base_uri: String,
+    /// Most eggs a wallet can buy
+    pub wallet_cap: u32,
+    /// Receives the proceeds
+    pub treasury: String,
+}
+
+impl SaleConfig {
+    pub fn validate(&self, api: &dyn Api) -> Result<(), ContractError> {
+        let invalid = |reason: &str| ContractError::InvalidSaleConfig {
+            reason: reason.to_string(),
+        };
+        match &self.payment {
+            SalePayment::Native { denom } if denom.is_empty() => {
+                return Err(invalid("denom cannot be empty"));
+            }
+            SalePayment::Native { .. } => {}
+            SalePayment::Cw20 { address } => {
+                api.addr_validate(address)?;
+            }
+        }
+        if self.base_uri.is_empty() {
+            return Err(invalid("base uri cannot be empty"));
+        }
+        if self.wallet_cap == 0 {
+            return Err(invalid("wallet cap cannot be zero"));
+        }
+        api.addr_validate(&self.treasury)?;
+        Ok(())
+    }
+
+    pub fn token_uri(&self, token_id: &str) -> String {
+        format!("{}/{}", self.base_uri.trim_end_matches('/'), token_id)
+    }
+}
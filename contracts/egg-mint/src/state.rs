@@ -1,5 +1,8 @@
-use cosmwasm_std::Uint64;
-use cw_storage_plus::Item;
+use crate::drand::DrandConfig;
+use crate::hatch::{HatchCommit, HatchConfig};
+use crate::sale::SaleConfig;
+use cosmwasm_std::{Addr, Binary, Empty, Uint64};
+use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -13,5 +16,39 @@ pub struct CollectionInfo {
     pub base_price: Uint64,
 }
 
+/// Egg whose dragon is being minted by a drand hatch.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingHatch {
+    pub token_id: String,
+    pub owner: Addr,
+    pub kind: String,
+}
+
 pub const COLLECTION_INFO: Item<CollectionInfo> = Item::new("collection_info");
 pub const OWNED_EGG_COUNT: Item<Uint64> = Item::new("owned_egg_count");
+pub const DRAGON_CONTRACT: Item<Addr> = Item::new("dragon_contract");
+/// Birth ids already minted, so a birth message cannot mint twice.
+pub const BIRTH_IDS: Map<&str, Empty> = Map::new("birth_ids");
+pub const HATCH_CONFIG: Item<HatchConfig> = Item::new("hatch_config");
+/// Kinds of the parents of each bred egg, which weight its hatch.
+pub const PARENT_KINDS: Map<&str, Vec<String>> = Map::new("parent_kinds");
+/// Time in seconds each egg can hatch from.
+pub const HATCH_TIMES: Map<&str, Uint64> = Map::new("hatch_times");
+/// Pending hatch commitment of each egg.
+pub const HATCH_COMMITS: Map<&str, HatchCommit> = Map::new("hatch_commits");
+/// Bumped on every hatch commit and reveal, mixed into the rolls.
+pub const HATCH_NONCE: Item<u64> = Item::new("hatch_nonce");
+pub const DRAND_CONFIG: Item<DrandConfig> = Item::new("drand_config");
+/// Verified randomness of each drand round submitted.
+pub const DRAND_BEACONS: Map<u64, Binary> = Map::new("drand_beacons");
+/// Eggs waiting for a drand round, by round.
+pub const DRAND_HATCHES: Map<(u64, &str), Empty> = Map::new("drand_hatches");
+/// drand round each waiting egg is settled by.
+pub const DRAND_HATCH_ROUNDS: Map<&str, u64> = Map::new("drand_hatch_rounds");
+/// Eggs of a drand round whose dragon mint has not replied yet, by reply id.
+pub const PENDING_HATCHES: Map<u64, PendingHatch> = Map::new("pending_hatches");
+pub const SALE_CONFIG: Item<SaleConfig> = Item::new("sale_config");
+/// Last token id assigned by the sale.
+pub const SALE_TOKEN_SEQ: Item<u64> = Item::new("sale_token_seq");
+/// Eggs bought by each wallet.
+pub const PURCHASES: Map<&Addr, u32> = Map::new("purchases");
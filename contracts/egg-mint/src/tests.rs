@@ -1,14 +1,33 @@
 #[cfg(test)]
 mod tests {
-    use crate::contract::{execute, instantiate, query, query_owned_egg_count};
-    use crate::msg::{CustomMintMsg, ExecuteMsg, Extension, InstantiateMsg, QueryMsg};
+    use crate::contract::{execute, instantiate, migrate, query, query_owned_egg_count, reply};
+    use crate::drand::DrandConfig;
+    use crate::hatch::{beacon_roll, reveal_roll, HatchCommit, HatchConfig, Rarity};
+    use crate::msg::{
+        CustomMintMsg, DragonExecuteMsg, DragonKind, DragonParent, DragonRewardSchedulesResponse,
+        ExecuteMsg, Extension, IncubationResponse, InstantiateMsg, Metadata, MigrateMsg, QueryMsg,
+        ReceiveMsg,
+    };
+    use crate::sale::{SaleConfig, SalePayment};
+    use crate::state::{COLLECTION_INFO, PARENT_KINDS};
+    use crate::ContractError;
+    use cw2::{get_contract_version, set_contract_version};
     use std::fs::read_to_string;
     //use crate::state::{CollectionInfo, Egg, COLLECTION_INFO, OWNED_EGG_COUNT};
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary, Empty, Uint64};
-    use cw721::{ContractInfoResponse, Cw721Query};
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{
+        coins, from_binary, to_binary, Addr, BankMsg, Binary, ContractResult, CosmosMsg, Empty,
+        OwnedDeps, Reply, ReplyOn, StdError, SubMsg, SubMsgResponse, SubMsgResult, SystemResult,
+        Timestamp, Uint128, Uint64, WasmMsg,
+    };
+    use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+    use cw721::{ContractInfoResponse, Cw721Query, NftInfoResponse, OwnerOfResponse};
     use cw721_base::msg::InstantiateMsg as Cw721InstantiateMsg;
     use cw721_base::{Cw721Contract, MintMsg};
+    use sha2::{Digest, Sha256};
+    use stake_dragons_utils::migrate::MigrateError;
 
     #[test]
     fn proper_initialization() {
@@ -25,6 +44,8 @@ mod tests {
             },
             base_price: Uint64::new(1),
             size: Uint64::new(100),
+            dragon_contract: None,
+            hatch_config: None,
         };
 
         let info = mock_info("creator", &coins(1000000, "earth"));
@@ -171,4 +192,1325 @@ mod tests {
         let res = query_owned_egg_count(deps.as_ref()).unwrap();
         println!("owned egg count from state {}", res.owned);
     }
+
+    #[test]
+    fn dragon_birth_mints_egg_with_lineage_once() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            base: Cw721InstantiateMsg {
+                name: String::from("collection0"),
+                symbol: String::from("EGG"),
+                minter: String::from("creator"),
+            },
+            base_price: Uint64::new(1),
+            size: Uint64::new(100),
+            dragon_contract: None,
+            hatch_config: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let birth = |id: &str, parents: Vec<DragonParent>| ExecuteMsg::DragonBirth {
+            id: id.to_string(),
+            owner: "alice".to_string(),
+            parents,
+        };
+        let parent = |token_id: &str, kind: &str| DragonParent {
+            token_id: token_id.to_string(),
+            kind: kind.to_string(),
+        };
+
+        // nobody may send births until the dragon contract is registered
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("dragons", &[]),
+            birth("00001", vec![parent("1", "rare")]),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let update = ExecuteMsg::UpdateDragonContract {
+            address: "dragons".to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mallory", &[]),
+            update.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            birth("00001", vec![parent("1", "rare")]),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("dragons", &[]),
+            birth("00001", vec![parent("1", "rare")]),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("dragons", &[]),
+            birth("breed1", vec![parent("1", "rare"), parent("2", "epic")]),
+        )
+        .unwrap();
+
+        let info: NftInfoResponse<Metadata> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::NftInfo {
+                    token_id: "breed1".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let attributes: Vec<(String, String)> = info
+            .extension
+            .attributes
+            .unwrap()
+            .into_iter()
+            .map(|attr| (attr.trait_type, attr.value))
+            .collect();
+        assert_eq!(
+            attributes,
+            [
+                ("origin", "bred"),
+                ("parent_1", "1"),
+                ("parent_1_kind", "rare"),
+                ("parent_2", "2"),
+                ("parent_2_kind", "epic"),
+            ]
+            .map(|(trait_type, value)| (trait_type.to_string(), value.to_string()))
+        );
+        // only bred eggs keep their parents' kinds for the hatch
+        assert_eq!(
+            PARENT_KINDS.load(&deps.storage, "breed1").unwrap(),
+            vec!["rare".to_string(), "epic".to_string()]
+        );
+        assert!(!PARENT_KINDS.has(&deps.storage, "00001"));
+        let owner: OwnerOfResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::OwnerOf {
+                    token_id: "00001".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(owner.owner, "alice");
+
+        // a birth id is never minted twice, even once its egg is gone
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            ExecuteMsg::Burn {
+                token_id: "00001".to_string(),
+            },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("dragons", &[]),
+            birth("00001", vec![parent("1", "rare")]),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::DuplicateBirth { .. }));
+    }
+
+    fn hatch_config(rarities: Vec<(&str, u32)>) -> HatchConfig {
+        HatchConfig {
+            incubation_period: Uint64::new(100),
+            reveal_delay: 2,
+            stale_after: 10,
+            rarities: rarities
+                .into_iter()
+                .map(|(kind, weight)| Rarity {
+                    kind: kind.to_string(),
+                    weight,
+                    ovulation_period: 30,
+                    daily_income: Uint128::new(10),
+                })
+                .collect(),
+        }
+    }
+
+    /// Answers dragon-mint's `RewardSchedules` query with a schedule for each of `kinds`.
+    fn mock_dragon_kinds(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, kinds: &[&str]) {
+        let res = to_binary(&DragonRewardSchedulesResponse {
+            schedules: kinds
+                .iter()
+                .map(|kind| DragonKind {
+                    kind: kind.to_string(),
+                })
+                .collect(),
+        })
+        .unwrap();
+        deps.querier
+            .update_wasm(move |_| SystemResult::Ok(ContractResult::Ok(res.clone())));
+    }
+
+    #[test]
+    fn hatch_config_kinds_must_be_minted_by_dragon_contract() {
+        let mut deps = mock_dependencies();
+        mock_dragon_kinds(&mut deps, &["common", "rare"]);
+        let msg = InstantiateMsg {
+            base: Cw721InstantiateMsg {
+                name: String::from("collection0"),
+                symbol: String::from("EGG"),
+                minter: String::from("creator"),
+            },
+            base_price: Uint64::new(1),
+            size: Uint64::new(100),
+            dragon_contract: None,
+            hatch_config: Some(hatch_config(vec![("common", 1), ("mythic", 1)])),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // the kinds are checked once the dragon contract is known
+        let update = ExecuteMsg::UpdateDragonContract {
+            address: "dragons".to_string(),
+        };
+        let err =
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidHatchConfig { .. }));
+        mock_dragon_kinds(&mut deps, &["common", "mythic"]);
+        let update = ExecuteMsg::UpdateDragonContract {
+            address: "dragons".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update).unwrap();
+
+        let update_config = |rarities| ExecuteMsg::UpdateHatchConfig {
+            config: hatch_config(rarities),
+        };
+        for rarities in [vec![("rare", 1)], vec![("Mythic", 1)]] {
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("creator", &[]),
+                update_config(rarities),
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::InvalidHatchConfig { .. }));
+        }
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            update_config(vec![("mythic", 1)]),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn hatch_commits_then_reveals_drawn_dragon() {
+        let mut deps = mock_dependencies();
+        mock_dragon_kinds(&mut deps, &["common", "legendary", "rare"]);
+        let config = hatch_config(vec![("common", 3), ("legendary", 0), ("rare", 1)]);
+        assert_eq!(config.draw(2, &[]).kind, "common");
+        assert_eq!(config.draw(3, &[]).kind, "rare");
+        assert_eq!(config.draw(7, &[]).kind, "rare");
+        let msg = InstantiateMsg {
+            base: Cw721InstantiateMsg {
+                name: String::from("collection0"),
+                symbol: String::from("EGG"),
+                minter: String::from("creator"),
+            },
+            base_price: Uint64::new(1),
+            size: Uint64::new(100),
+            dragon_contract: None,
+            hatch_config: Some(config.clone()),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let mint = ExecuteMsg::Mint(CustomMintMsg {
+            base: MintMsg {
+                token_id: "egg1".to_string(),
+                owner: "alice".to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            hatch: None,
+        });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), mint).unwrap();
+        let secret = Binary::from(b"alice secret".to_vec());
+        let commit = ExecuteMsg::CommitHatch {
+            token_id: "egg1".to_string(),
+            commitment: Binary::from(Sha256::digest(secret.as_slice()).to_vec()),
+        };
+        let hatch = |secret: &Binary| ExecuteMsg::Hatch {
+            token_id: "egg1".to_string(),
+            secret: secret.clone(),
+        };
+
+        // hatching needs the dragon contract to mint into
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            commit.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::HatchingDisabled {}));
+        let update = ExecuteMsg::UpdateDragonContract {
+            address: "dragons".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), update).unwrap();
+
+        let hatch_at = mock_env().block.time.seconds() + 100;
+        let incubation: IncubationResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Incubation {
+                    token_id: "egg1".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(incubation.hatch_at, Uint64::new(hatch_at));
+        assert!(!incubation.ready);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            commit.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::EggNotReady { hatch_at: h } if h.u64() == hatch_at));
+
+        let at_block = |blocks: u64| {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(100 + blocks * 5);
+            env.block.height += blocks;
+            env
+        };
+        let height = at_block(0).block.height;
+        let err = execute(
+            deps.as_mut(),
+            at_block(0),
+            mock_info("mallory", &[]),
+            commit.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let err = execute(
+            deps.as_mut(),
+            at_block(0),
+            mock_info("alice", &[]),
+            ExecuteMsg::CommitHatch {
+                token_id: "egg1".to_string(),
+                commitment: Binary::from(b"egg".to_vec()),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidCommitment {}));
+        execute(
+            deps.as_mut(),
+            at_block(0),
+            mock_info("alice", &[]),
+            commit.clone(),
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            at_block(1),
+            mock_info("alice", &[]),
+            commit.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::HatchCommitmentPending { reveal_from, .. } if reveal_from == height + 2
+        ));
+
+        // the secret is revealed after the delay, and has to match the commitment
+        let err = execute(
+            deps.as_mut(),
+            at_block(1),
+            mock_info("alice", &[]),
+            hatch(&secret),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::RevealTooEarly { reveal_from } if reveal_from == height + 2
+        ));
+        let err = execute(
+            deps.as_mut(),
+            at_block(2),
+            mock_info("alice", &[]),
+            hatch(&Binary::from(b"guess".to_vec())),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidReveal {}));
+
+        // a commitment left unrevealed cannot be replaced to roll again, and revealing it
+        // later does not change its roll
+        let err =
+            execute(deps.as_mut(), at_block(50), mock_info("alice", &[]), commit).unwrap_err();
+        assert!(matches!(err, ContractError::HatchCommitmentPending { .. }));
+        let stored: HatchCommit = from_binary(
+            &query(
+                deps.as_ref(),
+                at_block(50),
+                QueryMsg::HatchCommit {
+                    token_id: "egg1".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let expected = config
+            .draw(reveal_roll(&stored, &secret, "egg1"), &[])
+            .kind
+            .clone();
+        let err = execute(
+            deps.as_mut(),
+            at_block(50),
+            mock_info("mallory", &[]),
+            hatch(&secret),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = execute(
+            deps.as_mut(),
+            at_block(50),
+            mock_info("alice", &[]),
+            hatch(&secret),
+        )
+        .unwrap();
+        let kind = res
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "kind")
+            .unwrap()
+            .value
+            .clone();
+        assert_eq!(kind, expected);
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr, msg, ..
+            }) => {
+                assert_eq!(contract_addr, "dragons");
+                let DragonExecuteMsg::Mint(mint) = from_binary(msg).unwrap();
+                assert_eq!(mint.base.owner, "alice");
+                assert_eq!(mint.extension[0].trait_type, "kind");
+                assert_eq!(mint.extension[0].value, kind);
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+
+        // the egg and its commitment are gone
+        let commit: Option<HatchCommit> = from_binary(
+            &query(
+                deps.as_ref(),
+                at_block(7),
+                QueryMsg::HatchCommit {
+                    token_id: "egg1".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(commit, None);
+        assert!(query(
+            deps.as_ref(),
+            at_block(7),
+            QueryMsg::Incubation {
+                token_id: "egg1".to_string(),
+            },
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn reveal_rolls_follow_rarity_weights() {
+        let config = hatch_config(vec![("common", 60), ("rare", 30), ("legendary", 10)]);
+        let env = mock_env();
+        let commit = |nonce: u64| HatchCommit {
+            commitment: Binary::default(),
+            committer: Addr::unchecked("alice"),
+            height: env.block.height,
+            time: env.block.time,
+            nonce,
+        };
+        let mut counts = [0u32; 3];
+        for seed in 0..10_000u64 {
+            let roll = reveal_roll(&commit(seed), &seed.to_be_bytes(), "egg1");
+            let kind = &config.draw(roll, &[]).kind;
+            let i = config
+                .rarities
+                .iter()
+                .position(|r| &r.kind == kind)
+                .unwrap();
+            counts[i] += 1;
+        }
+        // within 2% of the expected 6000 / 3000 / 1000
+        assert!((5800..=6200).contains(&counts[0]), "{:?}", counts);
+        assert!((2800..=3200).contains(&counts[1]), "{:?}", counts);
+        assert!((800..=1200).contains(&counts[2]), "{:?}", counts);
+
+        // the same secret committed with another nonce or at another time rolls differently
+        let roll = reveal_roll(&commit(1), b"secret", "egg1");
+        assert_eq!(roll, reveal_roll(&commit(1), b"secret", "egg1"));
+        assert_ne!(roll, reveal_roll(&commit(2), b"secret", "egg1"));
+        let mut later = commit(1);
+        later.time = later.time.plus_nanos(1);
+        assert_ne!(roll, reveal_roll(&later, b"secret", "egg1"));
+    }
+
+    #[test]
+    fn bred_eggs_favour_their_parents_kinds() {
+        let config = hatch_config(vec![("common", 60), ("rare", 30), ("legendary", 10)]);
+        let share = |parent_kinds: &[String]| {
+            // a whole number of cycles of every total below
+            let mut counts = [0u32; 3];
+            for roll in 0..17_600u64 {
+                let kind = &config.draw(roll, parent_kinds).kind;
+                let i = config
+                    .rarities
+                    .iter()
+                    .position(|r| &r.kind == kind)
+                    .unwrap();
+                counts[i] += 1;
+            }
+            counts
+        };
+        assert_eq!(share(&[]), [10560, 5280, 1760]);
+        // two rare parents triple the rare weight: 60 / 90 / 10
+        let rare = vec!["rare".to_string(), "rare".to_string()];
+        assert_eq!(share(&rare), [6600, 9900, 1100]);
+        // a parent of a kind the table does not hatch adds nothing: 60 / 30 / 20
+        let mixed = vec!["legendary".to_string(), "mythic".to_string()];
+        assert_eq!(share(&mixed), [9600, 4800, 3200]);
+    }
+
+    // drand mainnet, round 72785
+    const DRAND_PUBLIC_KEY: &str = "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31";
+    const DRAND_PREVIOUS_SIGNATURE: &str = "a609e19a03c2fcc559e8dae14900aaefe517cb55c840f6e69bc8e4f66c8d18e8a609685d9917efbfb0c37f058c2de88f13d297c7e19e0ab24813079efe57a182554ff054c7638153f9b26a60e7111f71a0ff63d9571704905d3ca6df0b031747";
+    const DRAND_SIGNATURE: &str = "82f5d3d2de4db19d40a6980e8aa37842a0e55d1df06bd68bddc8d60002e8e959eb9cfa368b3c1b77d18f02a54fe047b80f0989315f83b12a74fd8679c4f12aae86eaf6ab5690b34f1fddd50ee3cc6f6cdf59e95526d5a5d82aaa84fa6f181e42";
+    const DRAND_RANDOMNESS: &str =
+        "8b676484b5fb1f37f9ec5c413d7d29883504e5b669f604a1ce68b3388e9ae3d9";
+
+    #[test]
+    fn drand_beacon_settles_waiting_hatches() {
+        let mut deps = mock_dependencies();
+        mock_dragon_kinds(&mut deps, &["common", "rare"]);
+        let config = hatch_config(vec![("common", 3), ("rare", 1)]);
+        let msg = InstantiateMsg {
+            base: Cw721InstantiateMsg {
+                name: String::from("collection0"),
+                symbol: String::from("EGG"),
+                minter: String::from("creator"),
+            },
+            base_price: Uint64::new(1),
+            size: Uint64::new(100),
+            dragon_contract: Some("dragons".to_string()),
+            hatch_config: Some(config.clone()),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        for (token_id, owner) in [("egg1", "alice"), ("egg2", "bob")] {
+            let mint = ExecuteMsg::Mint(CustomMintMsg {
+                base: MintMsg {
+                    token_id: token_id.to_string(),
+                    owner: owner.to_string(),
+                    token_uri: None,
+                    extension: None,
+                },
+                hatch: Some("0".to_string()),
+            });
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), mint).unwrap();
+        }
+        let commit = |token_id: &str| ExecuteMsg::CommitDrandHatch {
+            token_id: token_id.to_string(),
+        };
+        let beacon = |round: u64, signature: &str| ExecuteMsg::SubmitDrandBeacon {
+            round,
+            previous_signature: Some(Binary::from(hex::decode(DRAND_PREVIOUS_SIGNATURE).unwrap())),
+            signature: Binary::from(hex::decode(signature).unwrap()),
+        };
+        // 72783 rounds after genesis, so commitments wait for round 72785
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1595431050 + 72782 * 30 + 5);
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            commit("egg1"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::DrandDisabled {}));
+        let drand = DrandConfig {
+            public_key: Binary::from(hex::decode(DRAND_PUBLIC_KEY).unwrap()),
+            genesis_time: 1595431050,
+            period: 30,
+        };
+        let drand_config = |public_key: &str| ExecuteMsg::UpdateDrandConfig {
+            config: Some(DrandConfig {
+                public_key: Binary::from(hex::decode(public_key).unwrap()),
+                ..drand.clone()
+            }),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("mallory", &[]),
+            drand_config(DRAND_PUBLIC_KEY),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            drand_config(&DRAND_PUBLIC_KEY[2..]),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidDrandConfig { .. }));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            drand_config(DRAND_PUBLIC_KEY),
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("mallory", &[]),
+            commit("egg1"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            commit("egg1"),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "round" && attr.value == "72785"));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bob", &[]),
+            commit("egg2"),
+        )
+        .unwrap();
+        let round: Option<u64> = from_binary(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::DrandHatch {
+                    token_id: "egg1".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(round, Some(72785));
+        // a waiting egg cannot be committed again, by either scheme
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            commit("egg1"),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::DrandHatchPending { round: 72785, .. }
+        ));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            ExecuteMsg::CommitHatch {
+                token_id: "egg1".to_string(),
+                commitment: Binary::from(Sha256::digest(b"secret").to_vec()),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::DrandHatchPending { .. }));
+
+        // the network cannot be switched off or replaced while eggs wait for its rounds
+        for config in [
+            None,
+            Some(DrandConfig {
+                period: 3,
+                ..drand.clone()
+            }),
+        ] {
+            let err = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("creator", &[]),
+                ExecuteMsg::UpdateDrandConfig { config },
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::DrandHatchesWaiting {}));
+        }
+
+        // the signature has to be the network's for that round
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            beacon(72786, DRAND_SIGNATURE),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidBeacon { round: 72786 }));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            beacon(72785, DRAND_PREVIOUS_SIGNATURE),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidBeacon { round: 72785 }));
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            beacon(72785, DRAND_SIGNATURE),
+        )
+        .unwrap();
+        let randomness = hex::decode(DRAND_RANDOMNESS).unwrap();
+        let stored: Option<Binary> = from_binary(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::DrandBeacon { round: 72785 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stored, Some(Binary::from(randomness.clone())));
+        let minted = |msg: &SubMsg| match &msg.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+                let DragonExecuteMsg::Mint(mint) = from_binary(msg).unwrap();
+                (mint.base.owner, mint.extension[0].value.clone())
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        };
+        // each dragon is minted in its own submessage
+        let mut kinds = vec![];
+        assert_eq!(res.messages.len(), 2);
+        for (i, (token_id, owner)) in [("egg1", "alice"), ("egg2", "bob")].iter().enumerate() {
+            let kind = config
+                .draw(beacon_roll(&randomness, token_id), &[])
+                .kind
+                .clone();
+            assert_eq!(res.messages[i].id, i as u64 + 1);
+            assert_eq!(res.messages[i].reply_on, ReplyOn::Always);
+            assert_eq!(minted(&res.messages[i]), (owner.to_string(), kind.clone()));
+            kinds.push(kind);
+        }
+        let incubating = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, token_id: &str| {
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Incubation {
+                    token_id: token_id.to_string(),
+                },
+            )
+            .is_ok()
+        };
+        let waiting_round = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                             token_id: &str| {
+            let round: Option<u64> = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::DrandHatch {
+                        token_id: token_id.to_string(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            round
+        };
+        let minted_ok = |id: u64| Reply {
+            id,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+
+        // the egg is burnt once its dragon is minted
+        let res = reply(deps.as_mut(), env.clone(), minted_ok(1)).unwrap();
+        let attributes: Vec<(&str, &str)> = res.events[0]
+            .attributes
+            .iter()
+            .map(|attr| (attr.key.as_str(), attr.value.as_str()))
+            .collect();
+        assert_eq!(
+            attributes,
+            vec![
+                ("token_id", "egg1"),
+                ("owner", "alice"),
+                ("kind", kinds[0].as_str())
+            ]
+        );
+        assert!(!incubating(&deps, "egg1"));
+        assert_eq!(waiting_round(&deps, "egg1"), None);
+
+        // a failed mint keeps its egg waiting without undoing the other hatch
+        let failed = Reply {
+            id: 2,
+            result: SubMsgResult::Err("collection is full".to_string()),
+        };
+        let res = reply(deps.as_mut(), env.clone(), failed).unwrap();
+        assert!(res.events[0]
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "error" && attr.value == "collection is full"));
+        assert!(incubating(&deps, "egg2"));
+        assert_eq!(waiting_round(&deps, "egg2"), Some(72785));
+        let err = reply(deps.as_mut(), env.clone(), minted_ok(2)).unwrap_err();
+        assert!(matches!(err, ContractError::UnknownReplyId { id: 2 }));
+
+        // submitting the beacon again retries the egg left over
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            beacon(72785, DRAND_SIGNATURE),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            minted(&res.messages[0]),
+            ("bob".to_string(), kinds[1].clone())
+        );
+        reply(deps.as_mut(), env, minted_ok(1)).unwrap();
+        assert!(!incubating(&deps, "egg2"));
+        assert_eq!(waiting_round(&deps, "egg2"), None);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::UpdateDrandConfig { config: None },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn stale_hatch_commitment_moves_to_drand() {
+        let mut deps = mock_dependencies();
+        mock_dragon_kinds(&mut deps, &["common", "rare"]);
+        let msg = InstantiateMsg {
+            base: Cw721InstantiateMsg {
+                name: String::from("collection0"),
+                symbol: String::from("EGG"),
+                minter: String::from("creator"),
+            },
+            base_price: Uint64::new(1),
+            size: Uint64::new(100),
+            dragon_contract: Some("dragons".to_string()),
+            hatch_config: Some(hatch_config(vec![("common", 3), ("rare", 1)])),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let mint = ExecuteMsg::Mint(CustomMintMsg {
+            base: MintMsg {
+                token_id: "egg1".to_string(),
+                owner: "alice".to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            hatch: Some("0".to_string()),
+        });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), mint).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::UpdateDrandConfig {
+                config: Some(DrandConfig {
+                    public_key: Binary::from(hex::decode(DRAND_PUBLIC_KEY).unwrap()),
+                    genesis_time: 1595431050,
+                    period: 30,
+                }),
+            },
+        )
+        .unwrap();
+        // every block lands in the time that waits for round 72785
+        let height = mock_env().block.height;
+        let at_block = |offset: u64| {
+            let mut env = mock_env();
+            env.block.height = height + offset;
+            env.block.time = Timestamp::from_seconds(1595431050 + 72782 * 30 + 5);
+            env
+        };
+        execute(
+            deps.as_mut(),
+            at_block(0),
+            mock_info("alice", &[]),
+            ExecuteMsg::CommitHatch {
+                token_id: "egg1".to_string(),
+                commitment: Binary::from(Sha256::digest(b"lost secret").to_vec()),
+            },
+        )
+        .unwrap();
+
+        // a committed egg stays with the committer
+        let transfer = ExecuteMsg::TransferNft {
+            recipient: "bob".to_string(),
+            token_id: "egg1".to_string(),
+        };
+        let send = ExecuteMsg::SendNft {
+            contract: "market".to_string(),
+            token_id: "egg1".to_string(),
+            msg: Binary::default(),
+        };
+        for msg in [transfer.clone(), send] {
+            let err =
+                execute(deps.as_mut(), at_block(1), mock_info("alice", &[]), msg).unwrap_err();
+            assert!(matches!(
+                err,
+                ContractError::HatchCommittedEggCantBeTransferred { .. }
+            ));
+        }
+
+        // drand only takes over once the commitment is stale
+        let drand_hatch = ExecuteMsg::CommitDrandHatch {
+            token_id: "egg1".to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            at_block(9),
+            mock_info("alice", &[]),
+            drand_hatch.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::HatchCommitmentPending { stale_from, .. } if stale_from == height + 10
+        ));
+        let err = execute(
+            deps.as_mut(),
+            at_block(10),
+            mock_info("mallory", &[]),
+            drand_hatch.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(
+            deps.as_mut(),
+            at_block(10),
+            mock_info("alice", &[]),
+            drand_hatch,
+        )
+        .unwrap();
+        let commit: Option<HatchCommit> = from_binary(
+            &query(
+                deps.as_ref(),
+                at_block(10),
+                QueryMsg::HatchCommit {
+                    token_id: "egg1".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(commit, None);
+
+        // the round hatches the egg for whoever holds it by then
+        execute(
+            deps.as_mut(),
+            at_block(11),
+            mock_info("alice", &[]),
+            transfer,
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            at_block(12),
+            mock_info("anyone", &[]),
+            ExecuteMsg::SubmitDrandBeacon {
+                round: 72785,
+                previous_signature: Some(Binary::from(
+                    hex::decode(DRAND_PREVIOUS_SIGNATURE).unwrap(),
+                )),
+                signature: Binary::from(hex::decode(DRAND_SIGNATURE).unwrap()),
+            },
+        )
+        .unwrap();
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+                let DragonExecuteMsg::Mint(mint) = from_binary(msg).unwrap();
+                assert_eq!(mint.base.owner, "bob");
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+    }
+
+    fn sale_contract(payment: SalePayment) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            base: Cw721InstantiateMsg {
+                name: String::from("collection0"),
+                symbol: String::from("EGG"),
+                minter: String::from("creator"),
+            },
+            base_price: Uint64::new(100),
+            size: Uint64::new(5),
+            dragon_contract: None,
+            hatch_config: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let sale_config = ExecuteMsg::UpdateSaleConfig {
+            config: Some(SaleConfig {
+                payment,
+                base_uri: "ipfs://eggs/".to_string(),
+                wallet_cap: 2,
+                treasury: "treasury".to_string(),
+            }),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            sale_config,
+        )
+        .unwrap();
+        deps
+    }
+
+    #[test]
+    fn buy_egg_enforces_price_size_and_wallet_cap() {
+        let mut deps = sale_contract(SalePayment::Native {
+            denom: "uegg".to_string(),
+        });
+        let buy = |quantity: u32| ExecuteMsg::BuyEgg { quantity };
+        let sale_config = |wallet_cap: u32| ExecuteMsg::UpdateSaleConfig {
+            config: Some(SaleConfig {
+                payment: SalePayment::Native {
+                    denom: "uegg".to_string(),
+                },
+                base_uri: "ipfs://eggs/".to_string(),
+                wallet_cap,
+                treasury: "treasury".to_string(),
+            }),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mallory", &[]),
+            sale_config(3),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            sale_config(0),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidSaleConfig { .. }));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::UpdateSaleConfig { config: None },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &coins(100, "uegg")),
+            buy(1),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::SaleClosed {}));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            sale_config(2),
+        )
+        .unwrap();
+        // the sale skips ids the minter already used
+        let mint = ExecuteMsg::Mint(CustomMintMsg {
+            base: MintMsg {
+                token_id: "1".to_string(),
+                owner: "creator".to_string(),
+                token_uri: None,
+                extension: None,
+            },
+            hatch: None,
+        });
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), mint).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &coins(150, "uegg")),
+            buy(2),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, ContractError::InvalidPayment { ref expected } if expected == "200uegg")
+        );
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), buy(0)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidQuantity {}));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &coins(200, "uegg")),
+            buy(2),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "token_ids" && attr.value == "2,3"));
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(200, "uegg"),
+            })
+        );
+        let info: NftInfoResponse<Extension> = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::NftInfo {
+                    token_id: "3".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(info.token_uri, Some("ipfs://eggs/3".to_string()));
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &coins(100, "uegg")),
+            buy(1),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::WalletCapExceeded { cap: 2 }));
+        // three of the five eggs are minted
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &coins(300, "uegg")),
+            buy(3),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::SoldOut { remaining: 2 }));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob", &coins(200, "uegg")),
+            buy(2),
+        )
+        .unwrap();
+        let purchased: u32 = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Purchased {
+                    address: "bob".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(purchased, 2);
+        let owned = query_owned_egg_count(deps.as_ref()).unwrap();
+        assert_eq!(owned.owned, owned.size);
+    }
+
+    #[test]
+    fn buy_egg_with_cw20() {
+        let mut deps = sale_contract(SalePayment::Cw20 {
+            address: "token".to_string(),
+        });
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &coins(100, "uegg")),
+            ExecuteMsg::BuyEgg { quantity: 1 },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPayment { .. }));
+        let receive = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "alice".to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::BuyEgg { quantity: 1 }).unwrap(),
+            })
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other", &[]),
+            receive(100),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPayment { .. }));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("token", &[]),
+            receive(99),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPayment { .. }));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("token", &[]),
+            receive(100),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "treasury".to_string(),
+                    amount: Uint128::new(100),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        let owner: OwnerOfResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::OwnerOf {
+                    token_id: "1".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(owner.owner, "alice");
+    }
+
+    #[test]
+    fn free_eggs_forward_no_proceeds() {
+        for payment in [
+            SalePayment::Native {
+                denom: "uegg".to_string(),
+            },
+            SalePayment::Cw20 {
+                address: "token".to_string(),
+            },
+        ] {
+            let mut deps = sale_contract(payment.clone());
+            COLLECTION_INFO
+                .update::<_, StdError>(&mut deps.storage, |mut info| {
+                    info.base_price = Uint64::zero();
+                    Ok(info)
+                })
+                .unwrap();
+            let (sender, msg) = match payment {
+                SalePayment::Native { .. } => ("alice", ExecuteMsg::BuyEgg { quantity: 2 }),
+                SalePayment::Cw20 { .. } => (
+                    "token",
+                    ExecuteMsg::Receive(Cw20ReceiveMsg {
+                        sender: "alice".to_string(),
+                        amount: Uint128::zero(),
+                        msg: to_binary(&ReceiveMsg::BuyEgg { quantity: 2 }).unwrap(),
+                    }),
+                ),
+            };
+            let res = execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+            assert!(res.messages.is_empty());
+            let owner: OwnerOfResponse = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::OwnerOf {
+                        token_id: "2".to_string(),
+                        include_expired: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(owner.owner, "alice");
+        }
+    }
+
+    #[test]
+    fn migrate_checks_contract_and_version() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            base: Cw721InstantiateMsg {
+                name: String::from("collection0"),
+                symbol: String::from("EGG"),
+                minter: String::from("creator"),
+            },
+            base_price: Uint64::new(1),
+            size: Uint64::new(100),
+            dragon_contract: None,
+            hatch_config: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.contract, "crates.io:egg-mint");
+
+        // instances deployed before the fix carry the cw721-base version
+        set_contract_version(deps.as_mut().storage, "crates.io:cw721-base", "0.13.2").unwrap();
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.contract, "crates.io:egg-mint");
+
+        set_contract_version(deps.as_mut().storage, "crates.io:whitelist", "0.1.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Migrate(MigrateError::WrongContract { .. })
+        ));
+
+        set_contract_version(deps.as_mut().storage, "crates.io:egg-mint", "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Migrate(MigrateError::CannotDowngrade { .. })
+        ));
+
+        // any other cw721, such as dragon-mint v0.1, has no egg-mint state to take over
+        let mut deps = mock_dependencies();
+        set_contract_version(deps.as_mut().storage, "crates.io:cw721-base", "0.13.2").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Migrate(MigrateError::WrongContract { .. })
+        ));
+    }
 }
@@ -3,8 +3,9 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use stake_reward::msg::{ExecuteMsg, GetCountResponse, InstantiateMsg, QueryMsg};
+use stake_reward::msg::{ExecuteMsg, GetStateResponse, InstantiateMsg, QueryMsg};
 use stake_reward::state::State;
+use stake_dragons_utils::ownership::PendingOwnerResponse;
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -16,5 +17,6 @@ fn main() {
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(State), &out_dir);
-    export_schema(&schema_for!(GetCountResponse), &out_dir);
+    export_schema(&schema_for!(GetStateResponse), &out_dir);
+    export_schema(&schema_for!(PendingOwnerResponse), &out_dir);
 }
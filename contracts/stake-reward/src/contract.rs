@@ -1,14 +1,21 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, SubMsg, Uint128,
+    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, SubMsg,
+    Uint128,
+};
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
+use stake_dragons_utils::migrate::stored_version;
+use stake_dragons_utils::ownership::{
+    accept_ownership, cancel_ownership_proposal, propose_owner, query_pending_owner,
 };
-use cw2::set_contract_version;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, GetStateResponse, InstantiateMsg, QueryMsg};
+use crate::msg::{ExecuteMsg, GetStateResponse, InstantiateMsg, MigrateMsg, QueryMsg};
 use crate::state::{State, STATE};
 use cw20::{Cw20Contract, Cw20ExecuteMsg};
+use cw_utils::Expiration;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:stake-reward";
@@ -22,10 +29,10 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     let state = State {
-        owner: info.sender.into_string(),
-        dragon_contract: msg.dragon_contract,
+        owner: info.sender,
+        dragon_contract: deps.api.addr_validate(&msg.dragon_contract)?,
         cw20_contract: msg.cw20_contract,
-        admin: msg.admin,
+        admin: deps.api.addr_validate(&msg.admin)?,
     };
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     STATE.save(deps.storage, &state)?;
@@ -36,7 +43,7 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
@@ -48,8 +55,12 @@ pub fn execute(
             admin,
             dragon_contract,
             cw20_contract,
-            owner,
-        } => execute_edit_state(deps, info, admin, dragon_contract, owner, cw20_contract),
+        } => execute_edit_state(deps, info, admin, dragon_contract, cw20_contract),
+        ExecuteMsg::ProposeOwner { new_owner, expires } => {
+            execute_propose_owner(deps, env, info, new_owner, expires)
+        }
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, env, info),
+        ExecuteMsg::CancelOwnershipProposal {} => execute_cancel_ownership_proposal(deps, info),
     }
 }
 
@@ -62,7 +73,7 @@ pub fn execute_distribute_reward(
     let state = STATE.load(deps.storage)?;
 
     //Only owner and dragon contract can execute this message
-    if info.sender != state.owner || info.sender != state.dragon_contract {
+    if info.sender != state.owner && info.sender != state.dragon_contract {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -81,40 +92,115 @@ pub fn execute_edit_state(
     info: MessageInfo,
     admin: String,
     dragon_contract: String,
-    owner: String,
     cw20_contract: Addr,
 ) -> Result<Response, ContractError> {
     let state = STATE.load(deps.storage)?;
 
-    //Only owner and dragon contract can execute this message
-    if info.sender != state.owner || info.sender != state.admin {
+    //Only owner can execute this message, it decides who may pull rewards
+    if info.sender != state.owner {
         return Err(ContractError::Unauthorized {});
     }
 
+    // the owner only changes through ProposeOwner and AcceptOwnership
     let new_state = State {
-        owner,
-        dragon_contract,
+        owner: state.owner,
+        dragon_contract: deps.api.addr_validate(&dragon_contract)?,
         cw20_contract,
-        admin,
+        admin: deps.api.addr_validate(&admin)?,
     };
     STATE.save(deps.storage, &new_state)?;
 
     Ok(Response::new().add_attribute("method", "reset"))
 }
 
+pub fn execute_propose_owner(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_owner: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    let pending = propose_owner(deps.storage, &env.block, new_owner, expires)?;
+    Ok(Response::new()
+        .add_attribute("method", "propose_owner")
+        .add_attribute("pending_owner", pending.owner))
+}
+
+pub fn execute_accept_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    state.owner = accept_ownership(deps.storage, &env.block, &info.sender)?;
+    STATE.save(deps.storage, &state)?;
+    Ok(Response::new()
+        .add_attribute("method", "accept_ownership")
+        .add_attribute("new_owner", state.owner))
+}
+
+pub fn execute_cancel_ownership_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    cancel_ownership_proposal(deps.storage)?;
+    Ok(Response::new().add_attribute("method", "cancel_ownership_proposal"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    let stored_version = stored_version(
+        &stored.contract,
+        &stored.version,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        None,
+    )?;
+
+    if stored_version < Version::new(0, 2, 0) {
+        migrate_v0_1(deps.branch())?;
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+/// v0.1 stored owner, admin and dragon contract as unchecked strings.
+fn migrate_v0_1(deps: DepsMut) -> Result<(), ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    state.owner = deps.api.addr_validate(state.owner.as_str())?;
+    state.admin = deps.api.addr_validate(state.admin.as_str())?;
+    state.dragon_contract = deps.api.addr_validate(state.dragon_contract.as_str())?;
+    STATE.save(deps.storage, &state)?;
+    Ok(())
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetState {} => to_binary(&query_state(deps)?),
+        QueryMsg::PendingOwner {} => to_binary(&query_pending_owner(deps.storage)?),
     }
 }
 
 fn query_state(deps: Deps) -> StdResult<GetStateResponse> {
     let state = STATE.load(deps.storage)?;
     Ok(GetStateResponse {
-        owner: state.owner,
-        admin: state.admin,
-        dragon_contract: state.dragon_contract,
+        owner: state.owner.to_string(),
+        admin: state.admin.to_string(),
+        dragon_contract: state.dragon_contract.to_string(),
         cw20_contract: state.cw20_contract,
     })
 }
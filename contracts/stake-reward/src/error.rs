@@ -1,4 +1,6 @@
 use cosmwasm_std::StdError;
+use stake_dragons_utils::migrate::MigrateError;
+use stake_dragons_utils::ownership::OwnershipError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -11,6 +13,12 @@ pub enum ContractError {
 
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
+
+    #[error("{0}")]
+    Ownership(#[from] OwnershipError),
+
+    #[error("{0}")]
+    Migrate(#[from] MigrateError),
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
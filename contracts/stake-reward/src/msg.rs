@@ -1,4 +1,5 @@
 use cosmwasm_std::{Addr, Uint128};
+use cw_utils::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +10,9 @@ pub struct InstantiateMsg {
     pub cw20_contract: Addr,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
@@ -20,14 +24,21 @@ pub enum ExecuteMsg {
         admin: String,
         dragon_contract: String,
         cw20_contract: Addr,
-        owner: String,
     },
+    /// Starts a two-step ownership handover, completed by `AcceptOwnership` from `new_owner`.
+    ProposeOwner {
+        new_owner: String,
+        expires: Option<Expiration>,
+    },
+    AcceptOwnership {},
+    CancelOwnershipProposal {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     GetState {},
+    PendingOwner {},
 }
 
 // We define a custom struct for each query response
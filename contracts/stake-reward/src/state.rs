@@ -6,10 +6,10 @@ use cw_storage_plus::Item;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
-    pub owner: String,
-    pub dragon_contract: String,
+    pub owner: Addr,
+    pub dragon_contract: Addr,
     pub cw20_contract: Addr,
-    pub admin: String,
+    pub admin: Addr,
 }
 
 pub const STATE: Item<State> = Item::new("state");
@@ -0,0 +1,237 @@
+use crate::contract::{execute, instantiate, migrate, query};
+use crate::msg::{ExecuteMsg, GetStateResponse, InstantiateMsg, MigrateMsg, QueryMsg};
+use crate::ContractError;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{from_binary, Addr, Deps, DepsMut, Env, Response, StdError, Uint128};
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Item;
+use cw_utils::Expiration;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use stake_dragons_utils::migrate::MigrateError;
+use stake_dragons_utils::ownership::{OwnershipError, PendingOwnerResponse};
+
+const OWNER: &str = "owner";
+
+/// State as v0.1 stored it, with unchecked addresses.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct StateV0_1 {
+    owner: String,
+    dragon_contract: String,
+    cw20_contract: Addr,
+    admin: String,
+}
+
+const STATE_V0_1: Item<StateV0_1> = Item::new("state");
+
+fn setup_contract(deps: DepsMut) {
+    let msg = InstantiateMsg {
+        admin: "admin".to_string(),
+        dragon_contract: "dragon".to_string(),
+        cw20_contract: Addr::unchecked("token"),
+    };
+    instantiate(deps, mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+}
+
+fn save_v0_1_state(deps: DepsMut, admin: &str) {
+    let state = StateV0_1 {
+        owner: OWNER.to_string(),
+        dragon_contract: "dragon".to_string(),
+        cw20_contract: Addr::unchecked("token"),
+        admin: admin.to_string(),
+    };
+    STATE_V0_1.save(deps.storage, &state).unwrap();
+    set_contract_version(deps.storage, "crates.io:stake-reward", "0.1.0").unwrap();
+}
+
+fn execute_as(
+    deps: DepsMut,
+    env: Env,
+    sender: &str,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    execute(deps, env, mock_info(sender, &[]), msg)
+}
+
+fn query_owner(deps: Deps) -> String {
+    let state: GetStateResponse =
+        from_binary(&query(deps, mock_env(), QueryMsg::GetState {}).unwrap()).unwrap();
+    state.owner
+}
+
+fn query_pending_owner(deps: Deps) -> PendingOwnerResponse {
+    from_binary(&query(deps, mock_env(), QueryMsg::PendingOwner {}).unwrap()).unwrap()
+}
+
+#[test]
+fn ownership_handover_requires_acceptance() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    let propose = |expires: Option<Expiration>| ExecuteMsg::ProposeOwner {
+        new_owner: "new_owner".to_string(),
+        expires,
+    };
+
+    // the admin may not hand over the contract
+    let err = execute_as(deps.as_mut(), mock_env(), "admin", propose(None)).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+
+    execute_as(deps.as_mut(), mock_env(), OWNER, propose(None)).unwrap();
+    assert_eq!(
+        query_pending_owner(deps.as_ref()).pending_owner,
+        Some("new_owner".to_string())
+    );
+    let err = execute_as(
+        deps.as_mut(),
+        mock_env(),
+        "mallory",
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Ownership(OwnershipError::Unauthorized {})
+    ));
+    assert_eq!(query_owner(deps.as_ref()), OWNER);
+
+    execute_as(
+        deps.as_mut(),
+        mock_env(),
+        "new_owner",
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap();
+    assert_eq!(query_owner(deps.as_ref()), "new_owner");
+    assert_eq!(query_pending_owner(deps.as_ref()).pending_owner, None);
+}
+
+#[test]
+fn ownership_proposal_can_expire_or_be_cancelled() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    let deadline = Expiration::AtHeight(mock_env().block.height + 10);
+    let propose = ExecuteMsg::ProposeOwner {
+        new_owner: "new_owner".to_string(),
+        expires: Some(deadline),
+    };
+    execute_as(deps.as_mut(), mock_env(), OWNER, propose.clone()).unwrap();
+    assert_eq!(query_pending_owner(deps.as_ref()).expires, Some(deadline));
+
+    let mut late = mock_env();
+    late.block.height += 10;
+    let err = execute_as(
+        deps.as_mut(),
+        late,
+        "new_owner",
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Ownership(OwnershipError::OwnershipProposalExpired {})
+    ));
+
+    let cancel = ExecuteMsg::CancelOwnershipProposal {};
+    let err = execute_as(deps.as_mut(), mock_env(), "new_owner", cancel.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    execute_as(deps.as_mut(), mock_env(), OWNER, cancel.clone()).unwrap();
+    let err = execute_as(
+        deps.as_mut(),
+        mock_env(),
+        "new_owner",
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Ownership(OwnershipError::NoPendingOwner {})
+    ));
+    let err = execute_as(deps.as_mut(), mock_env(), OWNER, cancel).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Ownership(OwnershipError::NoPendingOwner {})
+    ));
+    assert_eq!(query_owner(deps.as_ref()), OWNER);
+}
+
+#[test]
+fn migrate_from_v0_1_validates_addresses() {
+    let mut deps = mock_dependencies();
+    save_v0_1_state(deps.as_mut(), "admin");
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    let state: GetStateResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetState {}).unwrap()).unwrap();
+    assert_eq!(state.owner, OWNER);
+    assert_eq!(state.admin, "admin");
+    assert_eq!(state.dragon_contract, "dragon");
+    let version = get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+    // an address v0.1 let through unchecked stops the migration
+    let mut deps = mock_dependencies();
+    save_v0_1_state(deps.as_mut(), "Admin");
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Std(StdError::GenericErr { .. })
+    ));
+    let version = get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.version, "0.1.0");
+}
+
+#[test]
+fn migrate_rejects_other_contracts_and_downgrades() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    set_contract_version(deps.as_mut().storage, "crates.io:dragon-mint", "0.1.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Migrate(MigrateError::WrongContract { .. })
+    ));
+
+    set_contract_version(deps.as_mut().storage, "crates.io:stake-reward", "99.0.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Migrate(MigrateError::CannotDowngrade { .. })
+    ));
+}
+
+#[test]
+fn rewards_are_paid_to_owner_or_dragon_contract_calls() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    let claim = || ExecuteMsg::Claim {
+        recipient: "alice".to_string(),
+        amount: Uint128::new(100),
+    };
+    for sender in ["dragon", OWNER] {
+        let res = execute_as(deps.as_mut(), mock_env(), sender, claim()).unwrap();
+        assert_eq!(res.messages.len(), 1);
+    }
+    for sender in ["admin", "alice"] {
+        let err = execute_as(deps.as_mut(), mock_env(), sender, claim()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+    }
+}
+
+#[test]
+fn only_owner_edits_state() {
+    let mut deps = mock_dependencies();
+    setup_contract(deps.as_mut());
+    let edit = || ExecuteMsg::EditState {
+        admin: "admin".to_string(),
+        dragon_contract: "other_dragon".to_string(),
+        cw20_contract: Addr::unchecked("other_token"),
+    };
+    let err = execute_as(deps.as_mut(), mock_env(), "admin", edit()).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    execute_as(deps.as_mut(), mock_env(), OWNER, edit()).unwrap();
+    let state: GetStateResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetState {}).unwrap()).unwrap();
+    assert_eq!(state.dragon_contract, "other_dragon");
+}
@@ -3,13 +3,14 @@ use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response, StdResult,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw_storage_plus::Bound;
 use cw_utils::maybe_addr;
+use stake_dragons_utils::migrate::stored_version;
 
 use crate::error::ContractError;
 use crate::msg::{
-    CustomMintMsg, ExecuteMsg, Extension, InstantiateMsg, MembersResponse, QueryMsg,
+    CustomMintMsg, ExecuteMsg, Extension, InstantiateMsg, MembersResponse, MigrateMsg, QueryMsg,
     WhitelistStateResponse,
 };
 use crate::state::{State, STATE, WHITELIST};
@@ -18,8 +19,10 @@ pub type Cw721Contract<'a> = cw721_base::Cw721Contract<'a, Extension, Empty>;
 pub type Cw721ExecuteMsg = cw721_base::ExecuteMsg<Extension>;
 
 // version info for migration info
-const CONTRACT_NAME: &str = "crates.io:egg-mint";
+const CONTRACT_NAME: &str = "crates.io:whitelist";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// v0.1 instances were instantiated under the egg-mint name
+const LEGACY_CONTRACT_NAME: &str = "crates.io:egg-mint";
 
 const PAGINATION_DEFAULT_LIMIT: u32 = 25;
 const PAGINATION_MAX_LIMIT: u32 = 100;
@@ -181,6 +184,30 @@ pub fn execute_remove_members(
         .add_attribute("sender", info.sender))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    // the egg-mint name only counts over whitelist state, never over an actual egg-mint
+    let legacy = STATE
+        .may_load(deps.storage)
+        .ok()
+        .flatten()
+        .map(|_| LEGACY_CONTRACT_NAME);
+    stored_version(
+        &stored.contract,
+        &stored.version,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+        legacy,
+    )?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -1,5 +1,6 @@
 use cosmwasm_std::StdError;
 use cw721_base::ContractError as Cw721ContractError;
+use stake_dragons_utils::migrate::MigrateError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -21,6 +22,9 @@ pub enum ContractError {
 
     #[error("NoMemberFound: {0}")]
     NoMemberFound(String),
+
+    #[error("{0}")]
+    Migrate(#[from] MigrateError),
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
@@ -1,11 +1,14 @@
 #[cfg(test)]
 mod tests {
-    use crate::contract::{execute, instantiate, query_members, query_state};
-    use crate::msg::{CustomMintMsg, ExecuteMsg, InstantiateMsg};
+    use crate::contract::{execute, instantiate, migrate, query_members, query_state};
+    use crate::msg::{CustomMintMsg, ExecuteMsg, InstantiateMsg, MigrateMsg};
+    use crate::ContractError;
+    use cw2::{get_contract_version, set_contract_version};
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
     use cosmwasm_std::DepsMut;
     use cw721_base::msg::InstantiateMsg as Cw721InstantiateMsg;
     use cw721_base::MintMsg;
+    use stake_dragons_utils::migrate::MigrateError;
 
     const ADMIN: &str = "minter";
 
@@ -138,4 +141,39 @@ mod tests {
         let res = query_state(deps.as_ref()).unwrap();
         println!("after rrrrrr mint again {}", res.claimed_dragons);
     }
+
+    #[test]
+    fn migrate_checks_contract_and_version() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut());
+
+        // instances deployed before the fix were named after egg-mint
+        set_contract_version(deps.as_mut().storage, "crates.io:egg-mint", "0.1.0").unwrap();
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.contract, "crates.io:whitelist");
+
+        set_contract_version(deps.as_mut().storage, "crates.io:dragon-mint", "0.1.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Migrate(MigrateError::WrongContract { .. })
+        ));
+
+        set_contract_version(deps.as_mut().storage, "crates.io:whitelist", "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Migrate(MigrateError::CannotDowngrade { .. })
+        ));
+
+        // an actual egg-mint has no whitelist state to take over
+        let mut deps = mock_dependencies();
+        set_contract_version(deps.as_mut().storage, "crates.io:egg-mint", "0.2.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Migrate(MigrateError::WrongContract { .. })
+        ));
+    }
 }
@@ -0,0 +1,3 @@
+pub mod migrate;
+pub mod ownership;
+pub mod traits;
@@ -0,0 +1,93 @@
+use cosmwasm_std::StdError;
+use semver::Version;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MigrateError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Cannot migrate from contract {contract}")]
+    WrongContract { contract: String },
+
+    #[error("Cannot migrate from version {stored} to older version {current}")]
+    CannotDowngrade { stored: String, current: String },
+}
+
+/// Version of the stored contract, once it is known to be an older `contract` that this code
+/// (at `version`) may replace. `legacy` is a name the contract was stored under before it set
+/// its own; the caller only passes it when the storage is known to be the contract's, and such
+/// instances count as v0.1.0.
+pub fn stored_version(
+    stored_contract: &str,
+    stored_version: &str,
+    contract: &str,
+    version: &str,
+    legacy: Option<&str>,
+) -> Result<Version, MigrateError> {
+    let stored = if stored_contract == contract {
+        parse_version(stored_version)?
+    } else if Some(stored_contract) == legacy {
+        Version::new(0, 1, 0)
+    } else {
+        return Err(MigrateError::WrongContract {
+            contract: stored_contract.to_string(),
+        });
+    };
+    if stored > parse_version(version)? {
+        return Err(MigrateError::CannotDowngrade {
+            stored: stored_version.to_string(),
+            current: version.to_string(),
+        });
+    }
+    Ok(stored)
+}
+
+fn parse_version(version: &str) -> Result<Version, StdError> {
+    version
+        .parse()
+        .map_err(|_| StdError::parse_err("Version", version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRACT: &str = "crates.io:dragon-mint";
+
+    #[test]
+    fn accepts_older_and_same_versions() {
+        let stored = stored_version(CONTRACT, "0.1.0", CONTRACT, "0.2.0", None).unwrap();
+        assert_eq!(stored, Version::new(0, 1, 0));
+        let stored = stored_version(CONTRACT, "0.2.0", CONTRACT, "0.2.0", None).unwrap();
+        assert_eq!(stored, Version::new(0, 2, 0));
+    }
+
+    #[test]
+    fn rejects_other_contracts_and_downgrades() {
+        let err =
+            stored_version("crates.io:egg-mint", "0.1.0", CONTRACT, "0.2.0", None).unwrap_err();
+        assert!(
+            matches!(err, MigrateError::WrongContract { contract } if contract == "crates.io:egg-mint")
+        );
+
+        let err = stored_version(CONTRACT, "0.3.0", CONTRACT, "0.2.0", None).unwrap_err();
+        assert!(matches!(err, MigrateError::CannotDowngrade { .. }));
+
+        let err = stored_version(CONTRACT, "latest", CONTRACT, "0.2.0", None).unwrap_err();
+        assert!(matches!(err, MigrateError::Std(StdError::ParseErr { .. })));
+    }
+
+    #[test]
+    fn legacy_name_counts_as_first_version() {
+        let legacy = Some("crates.io:cw721-base");
+        let stored =
+            stored_version("crates.io:cw721-base", "0.13.2", CONTRACT, "0.2.0", legacy).unwrap();
+        assert_eq!(stored, Version::new(0, 1, 0));
+
+        // the legacy name is nothing without the caller vouching for the storage
+        let err =
+            stored_version("crates.io:cw721-base", "0.13.2", CONTRACT, "0.2.0", None).unwrap_err();
+        assert!(matches!(err, MigrateError::WrongContract { .. }));
+    }
+}
@@ -0,0 +1,163 @@
+use cosmwasm_std::{Addr, BlockInfo, StdError, StdResult, Storage};
+use cw_storage_plus::Item;
+use cw_utils::Expiration;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum OwnershipError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("No ownership transfer is pending")]
+    NoPendingOwner {},
+
+    #[error("Ownership proposal has expired")]
+    OwnershipProposalExpired {},
+}
+
+/// Owner proposed by the current owner. The handover only happens once the proposed
+/// address accepts it, so a mistyped address can never take over the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingOwner {
+    pub owner: Addr,
+    pub expires: Option<Expiration>,
+}
+
+pub const PENDING_OWNER: Item<PendingOwner> = Item::new("pending_owner");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingOwnerResponse {
+    pub pending_owner: Option<String>,
+    pub expires: Option<Expiration>,
+}
+
+/// Records `new_owner` as the pending owner, replacing any previous proposal.
+pub fn propose_owner(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    new_owner: Addr,
+    expires: Option<Expiration>,
+) -> Result<PendingOwner, OwnershipError> {
+    if let Some(expires) = expires {
+        if expires.is_expired(block) {
+            return Err(OwnershipError::OwnershipProposalExpired {});
+        }
+    }
+    let pending = PendingOwner {
+        owner: new_owner,
+        expires,
+    };
+    PENDING_OWNER.save(storage, &pending)?;
+    Ok(pending)
+}
+
+/// Consumes the proposal and returns the new owner if `sender` is the pending owner.
+pub fn accept_ownership(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    sender: &Addr,
+) -> Result<Addr, OwnershipError> {
+    let pending = PENDING_OWNER
+        .may_load(storage)?
+        .ok_or(OwnershipError::NoPendingOwner {})?;
+    if pending.owner != *sender {
+        return Err(OwnershipError::Unauthorized {});
+    }
+    if let Some(expires) = pending.expires {
+        if expires.is_expired(block) {
+            return Err(OwnershipError::OwnershipProposalExpired {});
+        }
+    }
+    PENDING_OWNER.remove(storage);
+    Ok(pending.owner)
+}
+
+pub fn cancel_ownership_proposal(storage: &mut dyn Storage) -> Result<(), OwnershipError> {
+    if PENDING_OWNER.may_load(storage)?.is_none() {
+        return Err(OwnershipError::NoPendingOwner {});
+    }
+    PENDING_OWNER.remove(storage);
+    Ok(())
+}
+
+pub fn query_pending_owner(storage: &dyn Storage) -> StdResult<PendingOwnerResponse> {
+    let pending = PENDING_OWNER.may_load(storage)?;
+    Ok(PendingOwnerResponse {
+        pending_owner: pending.as_ref().map(|pending| pending.owner.to_string()),
+        expires: pending.and_then(|pending| pending.expires),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, MockStorage};
+
+    #[test]
+    fn handover_needs_acceptance_by_the_proposed_owner() {
+        let mut storage = MockStorage::new();
+        let block = mock_env().block;
+        propose_owner(&mut storage, &block, Addr::unchecked("new_owner"), None).unwrap();
+        let pending = query_pending_owner(&storage).unwrap();
+        assert_eq!(pending.pending_owner, Some("new_owner".to_string()));
+
+        let err = accept_ownership(&mut storage, &block, &Addr::unchecked("mallory")).unwrap_err();
+        assert_eq!(err, OwnershipError::Unauthorized {});
+
+        let owner = accept_ownership(&mut storage, &block, &Addr::unchecked("new_owner")).unwrap();
+        assert_eq!(owner, "new_owner");
+        assert_eq!(query_pending_owner(&storage).unwrap().pending_owner, None);
+        let err = accept_ownership(&mut storage, &block, &owner).unwrap_err();
+        assert_eq!(err, OwnershipError::NoPendingOwner {});
+    }
+
+    #[test]
+    fn proposal_expires() {
+        let mut storage = MockStorage::new();
+        let block = mock_env().block;
+        let err = propose_owner(
+            &mut storage,
+            &block,
+            Addr::unchecked("new_owner"),
+            Some(Expiration::AtHeight(block.height)),
+        )
+        .unwrap_err();
+        assert_eq!(err, OwnershipError::OwnershipProposalExpired {});
+
+        let deadline = Expiration::AtHeight(block.height + 10);
+        propose_owner(
+            &mut storage,
+            &block,
+            Addr::unchecked("new_owner"),
+            Some(deadline),
+        )
+        .unwrap();
+        assert_eq!(
+            query_pending_owner(&storage).unwrap().expires,
+            Some(deadline)
+        );
+        let mut late = block.clone();
+        late.height += 10;
+        let err = accept_ownership(&mut storage, &late, &Addr::unchecked("new_owner")).unwrap_err();
+        assert_eq!(err, OwnershipError::OwnershipProposalExpired {});
+    }
+
+    #[test]
+    fn cancel_drops_the_proposal() {
+        let mut storage = MockStorage::new();
+        let block = mock_env().block;
+        let err = cancel_ownership_proposal(&mut storage).unwrap_err();
+        assert_eq!(err, OwnershipError::NoPendingOwner {});
+
+        propose_owner(&mut storage, &block, Addr::unchecked("new_owner"), None).unwrap();
+        cancel_ownership_proposal(&mut storage).unwrap();
+        let err =
+            accept_ownership(&mut storage, &block, &Addr::unchecked("new_owner")).unwrap_err();
+        assert_eq!(err, OwnershipError::NoPendingOwner {});
+    }
+}
@@ -0,0 +1,37 @@
+//! Dragon traits checked both by dragon-mint when minting and by the contracts configuring
+//! what they mint.
+
+/// Longest ovulation period a dragon can be minted with, in days.
+pub const MAX_OVULATION_PERIOD: u64 = 365;
+
+/// Longest kind name.
+pub const MAX_KIND_LENGTH: usize = 32;
+
+/// Kinds are open, dragon-mint accepts any kind it has a reward schedule for, but their names
+/// are lowercase ascii letters, digits, `_` or `-`.
+pub fn is_valid_kind(kind: &str) -> bool {
+    !kind.is_empty()
+        && kind.len() <= MAX_KIND_LENGTH
+        && kind
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_' || b == b'-')
+}
+
+pub fn is_valid_ovulation_period(days: u64) -> bool {
+    (1..=MAX_OVULATION_PERIOD).contains(&days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_are_short_lowercase_names() {
+        assert!(is_valid_kind("common"));
+        assert!(is_valid_kind("mythic_2"));
+        assert!(!is_valid_kind(""));
+        assert!(!is_valid_kind("Rare"));
+        assert!(!is_valid_kind("rare dragon"));
+        assert!(!is_valid_kind(&"a".repeat(MAX_KIND_LENGTH + 1)));
+    }
+}